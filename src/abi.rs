@@ -0,0 +1,892 @@
+use pgrx::prelude::*;
+
+use alloy::core::hex;
+use alloy::dyn_abi::{DynSolType, DynSolValue, EventExt, JsonAbiExt, Specifier};
+use alloy::json_abi::{Event, Function, JsonAbi};
+use alloy::primitives::B256;
+
+use serde_json::{Map, Value};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::decode::Error;
+
+#[pg_schema]
+#[allow(non_snake_case)]
+mod ABI {
+    use pgrx::prelude::*;
+
+    use alloy::core::hex;
+
+    use super::{
+        decode, decode_any_log, decode_calldata, decode_log, encode, encode_calldata,
+        encode_packed, encode_topic, parse_topics, register, selector, topic_words,
+    };
+    use crate::decode::OrReport;
+
+    extension_sql!(
+        r#"
+CREATE TABLE ABI.registry (
+    contract text NOT NULL,
+    kind text NOT NULL CHECK (kind IN ('event', 'function')),
+    name text NOT NULL,
+    signature text NOT NULL,
+    selector bytea NOT NULL,
+    item jsonb NOT NULL,
+    PRIMARY KEY (contract, kind, signature)
+);
+
+CREATE INDEX abi_registry_selector ON ABI.registry (selector);
+
+SELECT pg_catalog.pg_extension_config_dump('ABI.registry', '');
+"#,
+        name = "abi_registry",
+    );
+
+    #[pg_extern(name = "decode", immutable, parallel_safe)]
+    fn abi_decode(signature: &str, data: &str) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode(
+            signature,
+            &crate::decode::hex(data).or_report(),
+        )?))
+    }
+
+    #[pg_extern(name = "decode", immutable, parallel_safe)]
+    fn abi_decode_bytea(signature: &str, data: &[u8]) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode(signature, data)?))
+    }
+
+    #[pg_extern(name = "try_decode", immutable, parallel_safe)]
+    fn abi_try_decode(signature: &str, data: &str) -> Option<pgrx::JsonB> {
+        decode(signature, &crate::decode::hex(data).ok()?)
+            .ok()
+            .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "try_decode", immutable, parallel_safe)]
+    fn abi_try_decode_bytea(signature: &str, data: &[u8]) -> Option<pgrx::JsonB> {
+        decode(signature, data).ok().map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "encode", immutable, parallel_safe)]
+    fn abi_encode(types: &str, values: pgrx::JsonB) -> Result<Vec<u8>, anyhow::Error> {
+        encode(types, &values.0)
+    }
+
+    #[pg_extern(name = "encode_packed", immutable, parallel_safe)]
+    fn abi_encode_packed(types: &str, values: pgrx::JsonB) -> Result<Vec<u8>, anyhow::Error> {
+        encode_packed(types, &values.0)
+    }
+
+    #[pg_extern(name = "encode_topic", immutable, parallel_safe)]
+    fn abi_encode_topic(ty: &str, value: &str) -> Result<String, anyhow::Error> {
+        Ok(hex::encode_prefixed(encode_topic(ty, value)?))
+    }
+
+    #[pg_extern(name = "decode_log", immutable, parallel_safe)]
+    fn abi_decode_log(
+        event: &str,
+        topics: Array<&str>,
+        data: &str,
+    ) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode_log(
+            event,
+            &parse_topics(topics.iter()).or_report(),
+            &crate::decode::hex(data).or_report(),
+        )?))
+    }
+
+    #[pg_extern(name = "decode_log", immutable, parallel_safe)]
+    fn abi_decode_log_bytea(
+        event: &str,
+        topics: Array<&str>,
+        data: &[u8],
+    ) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode_log(
+            event,
+            &parse_topics(topics.iter()).or_report(),
+            data,
+        )?))
+    }
+
+    #[pg_extern(name = "try_decode_log", immutable, parallel_safe)]
+    fn abi_try_decode_log(event: &str, topics: Array<&str>, data: &str) -> Option<pgrx::JsonB> {
+        decode_log(
+            event,
+            &parse_topics(topics.iter()).ok()?,
+            &crate::decode::hex(data).ok()?,
+        )
+        .ok()
+        .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "try_decode_log", immutable, parallel_safe)]
+    fn abi_try_decode_log_bytea(
+        event: &str,
+        topics: Array<&str>,
+        data: &[u8],
+    ) -> Option<pgrx::JsonB> {
+        decode_log(event, &parse_topics(topics.iter()).ok()?, data)
+            .ok()
+            .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "decode_log", immutable, parallel_safe)]
+    fn abi_decode_log_bytea_topics(
+        event: &str,
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode_log(
+            event,
+            &topic_words(topics.iter()).or_report(),
+            data,
+        )?))
+    }
+
+    #[pg_extern(name = "try_decode_log", immutable, parallel_safe)]
+    fn abi_try_decode_log_bytea_topics(
+        event: &str,
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::JsonB> {
+        decode_log(event, &topic_words(topics.iter()).ok()?, data)
+            .ok()
+            .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "selector", immutable, parallel_safe)]
+    fn abi_selector(signature: &str) -> Result<String, anyhow::Error> {
+        selector(signature)
+    }
+
+    #[pg_extern(name = "decode_calldata", immutable, parallel_safe)]
+    fn abi_decode_calldata(signature: &str, input: &str) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode_calldata(
+            signature,
+            &crate::decode::hex(input).or_report(),
+        )?))
+    }
+
+    #[pg_extern(name = "decode_calldata", immutable, parallel_safe)]
+    fn abi_decode_calldata_bytea(
+        signature: &str,
+        input: &[u8],
+    ) -> Result<pgrx::JsonB, anyhow::Error> {
+        Ok(pgrx::JsonB(decode_calldata(signature, input)?))
+    }
+
+    #[pg_extern(name = "try_decode_calldata", immutable, parallel_safe)]
+    fn abi_try_decode_calldata(signature: &str, input: &str) -> Option<pgrx::JsonB> {
+        decode_calldata(signature, &crate::decode::hex(input).ok()?)
+            .ok()
+            .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "try_decode_calldata", immutable, parallel_safe)]
+    fn abi_try_decode_calldata_bytea(signature: &str, input: &[u8]) -> Option<pgrx::JsonB> {
+        decode_calldata(signature, input).ok().map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "encode_calldata", immutable, parallel_safe)]
+    fn abi_encode_calldata(signature: &str, args: pgrx::JsonB) -> Result<String, anyhow::Error> {
+        Ok(hex::encode_prefixed(encode_calldata(signature, args.0)?))
+    }
+
+    #[pg_extern(name = "register")]
+    fn abi_register(name: &str, abi: pgrx::JsonB) -> Result<i64, anyhow::Error> {
+        register(name, serde_json::from_value(abi.0)?)
+    }
+
+    #[pg_extern(name = "decode_any_log", stable, parallel_safe)]
+    fn abi_decode_any_log(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Result<Option<pgrx::JsonB>, anyhow::Error> {
+        Ok(decode_any_log(
+            &parse_topics(topics.iter()).or_report(),
+            &crate::decode::hex(data).or_report(),
+        )?
+        .map(pgrx::JsonB))
+    }
+
+    #[pg_extern(name = "decode_any_log", stable, parallel_safe)]
+    fn abi_decode_any_log_bytea(
+        topics: Array<&str>,
+        data: &[u8],
+    ) -> Result<Option<pgrx::JsonB>, anyhow::Error> {
+        Ok(decode_any_log(&parse_topics(topics.iter()).or_report(), data)?.map(pgrx::JsonB))
+    }
+
+    #[pg_extern(name = "try_decode_any_log", stable, parallel_safe)]
+    fn abi_try_decode_any_log(topics: Array<&str>, data: &str) -> Option<pgrx::JsonB> {
+        decode_any_log(
+            &parse_topics(topics.iter()).ok()?,
+            &crate::decode::hex(data).ok()?,
+        )
+        .ok()
+        .flatten()
+        .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "try_decode_any_log", stable, parallel_safe)]
+    fn abi_try_decode_any_log_bytea(topics: Array<&str>, data: &[u8]) -> Option<pgrx::JsonB> {
+        decode_any_log(&parse_topics(topics.iter()).ok()?, data)
+            .ok()
+            .flatten()
+            .map(pgrx::JsonB)
+    }
+
+    #[pg_extern(name = "decode_any_log", stable, parallel_safe)]
+    fn abi_decode_any_log_bytea_topics(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Result<Option<pgrx::JsonB>, anyhow::Error> {
+        Ok(decode_any_log(&topic_words(topics.iter()).or_report(), data)?.map(pgrx::JsonB))
+    }
+
+    #[pg_extern(name = "try_decode_any_log", stable, parallel_safe)]
+    fn abi_try_decode_any_log_bytea_topics(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::JsonB> {
+        decode_any_log(&topic_words(topics.iter()).ok()?, data)
+            .ok()
+            .flatten()
+            .map(pgrx::JsonB)
+    }
+}
+
+/// Parses a Solidity type string, a bare type such as `uint256` is treated as a single element tuple.
+pub fn parse_params(signature: &str) -> Result<DynSolType> {
+    Ok(match DynSolType::parse(signature)? {
+        tuple @ DynSolType::Tuple(_) => tuple,
+        single => DynSolType::Tuple(vec![single]),
+    })
+}
+
+fn decode(signature: &str, data: &[u8]) -> Result<Value> {
+    let decoded = parse_params(signature)?.abi_decode_params(data)?;
+    Ok(to_json(&decoded))
+}
+
+/// Encodes values given as a jsonb array, the inverse of `decode`.
+fn encode(types: &str, values: &Value) -> Result<Vec<u8>> {
+    Ok(from_json(&parse_params(types)?, values)?.abi_encode_params())
+}
+
+/// Non-standard packed encoding, the equivalent of `abi.encodePacked`.
+fn encode_packed(types: &str, values: &Value) -> Result<Vec<u8>> {
+    Ok(from_json(&parse_params(types)?, values)?.abi_encode_packed())
+}
+
+/// Encodes a value the way it appears as an indexed event parameter, dynamic types such as
+/// `string`, `bytes`, arrays and tuples are hashed.
+fn encode_topic(ty: &str, value: &str) -> Result<B256> {
+    Ok(DynSolType::parse(ty)?.coerce_str(value)?.encode_topic())
+}
+
+pub fn parse_topics<'a>(
+    topics: impl IntoIterator<Item = Option<&'a str>>,
+) -> crate::decode::Result<Vec<B256>> {
+    topics
+        .into_iter()
+        .map(|topic| {
+            crate::decode::h256(topic.ok_or_else(|| Error::data("topics must not contain NULL"))?)
+        })
+        .collect()
+}
+
+/// Same as `parse_topics` for topics stored as raw 32 byte words.
+pub fn topic_words<'a>(
+    topics: impl IntoIterator<Item = Option<&'a [u8]>>,
+) -> crate::decode::Result<Vec<B256>> {
+    topics
+        .into_iter()
+        .map(|topic| {
+            let topic = topic.ok_or_else(|| Error::data("topics must not contain NULL"))?;
+            B256::try_from(topic)
+                .map_err(|_| Error::data(format!("topic must be 32 bytes, got {}", topic.len())))
+        })
+        .collect()
+}
+
+fn decode_log(signature: &str, topics: &[B256], data: &[u8]) -> Result<Value> {
+    let event = Event::parse(signature)?;
+    Ok(Value::Object(decode_event(&event, topics, data)?))
+}
+
+/// Decodes a log into an object keyed by parameter name, unnamed parameters are keyed by position.
+/// Topic0 has to match the selector of the event unless the event is anonymous.
+pub fn decode_event(event: &Event, topics: &[B256], data: &[u8]) -> Result<Map<String, Value>> {
+    let decoded = event.decode_log_parts(topics.iter().copied(), data)?;
+
+    let mut indexed = decoded.indexed.iter();
+    let mut body = decoded.body.iter();
+
+    let mut args = Map::new();
+    for (position, input) in event.inputs.iter().enumerate() {
+        let value = match input.indexed {
+            true => indexed.next(),
+            false => body.next(),
+        }
+        .ok_or_else(|| anyhow!("missing value for parameter {position}"))?;
+
+        args.insert(param_key(position, &input.name), to_json(value));
+    }
+
+    Ok(args)
+}
+
+/// Arguments are keyed by parameter name, unnamed parameters fall back to their position.
+fn param_key(position: usize, name: &str) -> String {
+    match name.is_empty() {
+        true => position.to_string(),
+        false => name.to_string(),
+    }
+}
+
+/// Returns the 4-byte selector of a function, parameter names and the `function` keyword are ignored.
+fn selector(signature: &str) -> Result<String> {
+    Ok(hex::encode_prefixed(Function::parse(signature)?.selector()))
+}
+
+fn decode_calldata(signature: &str, input: &[u8]) -> Result<Value> {
+    let function = Function::parse(signature)?;
+
+    if input.len() < 4 {
+        bail!(
+            "invalid calldata: expected at least 4 bytes, got {}",
+            input.len()
+        );
+    }
+
+    let (selector, data) = input.split_at(4);
+    if selector != function.selector().as_slice() {
+        bail!(
+            "invalid function selector: expected {}, got {}",
+            function.selector(),
+            hex::encode_prefixed(selector)
+        );
+    }
+
+    let values = function.abi_decode_input(data)?;
+
+    let mut args = Map::new();
+    for (position, (input, value)) in function.inputs.iter().zip(&values).enumerate() {
+        args.insert(param_key(position, &input.name), to_json(value));
+    }
+
+    Ok(Value::Object(args))
+}
+
+/// Encodes a call from either a positional array or an object keyed like `decode_calldata` output.
+fn encode_calldata(signature: &str, args: Value) -> Result<Vec<u8>> {
+    let function = Function::parse(signature)?;
+
+    let args = match args {
+        Value::Array(args) => args,
+        Value::Object(mut named) => {
+            let args = function
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(position, input)| {
+                    let key = param_key(position, &input.name);
+                    named
+                        .remove(&key)
+                        .ok_or_else(|| anyhow!("missing argument \"{key}\""))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if let Some(key) = named.keys().next() {
+                bail!("unknown argument \"{key}\"");
+            }
+
+            args
+        }
+        _ => bail!("arguments must be a jsonb array or object"),
+    };
+
+    if args.len() != function.inputs.len() {
+        bail!(
+            "expected {} arguments, got {}",
+            function.inputs.len(),
+            args.len()
+        );
+    }
+
+    let values = function
+        .inputs
+        .iter()
+        .zip(&args)
+        .map(|(input, arg)| from_json(&input.resolve()?, arg))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(function.abi_encode_input(&values)?)
+}
+
+/// Stores the events and functions of a JSON ABI under `contract`, replacing whatever was
+/// registered under that name before. Anonymous events have no topic0 and are skipped.
+fn register(contract: &str, abi: JsonAbi) -> Result<i64> {
+    let mut entries = Vec::new();
+
+    for event in abi.events().filter(|event| !event.anonymous) {
+        entries.push((
+            "event",
+            event.name.clone(),
+            event.signature(),
+            event.selector().to_vec(),
+            serde_json::to_value(event)?,
+        ));
+    }
+
+    for function in abi.functions() {
+        entries.push((
+            "function",
+            function.name.clone(),
+            function.signature(),
+            function.selector().to_vec(),
+            serde_json::to_value(function)?,
+        ));
+    }
+
+    Spi::connect_mut(|client| {
+        client.update(
+            "DELETE FROM ABI.registry WHERE contract = $1;",
+            None,
+            &[contract.into()],
+        )?;
+
+        for (kind, name, signature, selector, item) in entries.iter().cloned() {
+            client.update(
+                "INSERT INTO ABI.registry (contract, kind, name, signature, selector, item) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING;",
+                None,
+                &[
+                    contract.into(),
+                    kind.into(),
+                    name.into(),
+                    signature.into(),
+                    selector.into(),
+                    pgrx::JsonB(item).into(),
+                ],
+            )?;
+        }
+
+        Ok::<_, anyhow::Error>(entries.len() as i64)
+    })
+}
+
+/// Looks up topic0 in the registry and decodes the log with the first event that fits, events
+/// sharing a selector (e.g. ERC20 and ERC721 Transfer) differ in their indexed parameters.
+/// Returns `None` when no registered event has that selector.
+fn decode_any_log(topics: &[B256], data: &[u8]) -> Result<Option<Value>> {
+    let Some(topic0) = topics.first() else {
+        return Ok(None);
+    };
+
+    let candidates = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT contract, item FROM ABI.registry WHERE kind = 'event' AND selector = $1 ORDER BY contract;",
+            None,
+            &[topic0.to_vec().into()],
+        )?;
+
+        let mut candidates = Vec::new();
+        for row in table {
+            let contract = row.get_by_name::<String, _>("contract")?;
+            let item = row.get_by_name::<pgrx::JsonB, _>("item")?;
+
+            if let (Some(contract), Some(item)) = (contract, item) {
+                candidates.push((contract, item.0));
+            }
+        }
+
+        Ok::<_, anyhow::Error>(candidates)
+    })?;
+
+    let mut error = None;
+    for (contract, item) in candidates {
+        let event: Event = serde_json::from_value(item)?;
+
+        match decode_event(&event, topics, data) {
+            Ok(args) => {
+                return Ok(Some(serde_json::json!({
+                    "contract": contract,
+                    "event": event.name,
+                    "args": args,
+                })))
+            }
+            Err(err) => error = Some(err),
+        }
+    }
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(None),
+    }
+}
+
+/// Converts a decoded value into JSON, integers are emitted as decimal strings so they keep
+/// their full 256-bit precision and byte values as 0x-prefixed hex.
+pub fn to_json(value: &DynSolValue) -> Value {
+    match value {
+        DynSolValue::Bool(boolean) => Value::Bool(*boolean),
+        DynSolValue::Int(int, _) => Value::String(int.to_string()),
+        DynSolValue::Uint(uint, _) => Value::String(uint.to_string()),
+        DynSolValue::FixedBytes(word, size) => Value::String(hex::encode_prefixed(&word[..*size])),
+        DynSolValue::Address(address) => Value::String(hex::encode_prefixed(address)),
+        DynSolValue::Function(function) => Value::String(hex::encode_prefixed(function)),
+        DynSolValue::Bytes(bytes) => Value::String(hex::encode_prefixed(bytes)),
+        DynSolValue::String(string) => Value::String(string.clone()),
+        DynSolValue::Array(values)
+        | DynSolValue::FixedArray(values)
+        | DynSolValue::Tuple(values) => Value::Array(values.iter().map(to_json).collect()),
+    }
+}
+
+/// Converts JSON into a value of the given type, the inverse of `to_json`. Scalars can be passed as
+/// strings, numbers or booleans while arrays and tuples are JSON arrays. Integers that do not fit
+/// in a double have to be passed as strings.
+pub fn from_json(ty: &DynSolType, value: &Value) -> Result<DynSolValue> {
+    Ok(match (ty, value) {
+        (DynSolType::Array(inner), Value::Array(items)) => DynSolValue::Array(
+            items
+                .iter()
+                .map(|item| from_json(inner, item))
+                .collect::<Result<_>>()?,
+        ),
+        (DynSolType::FixedArray(inner, len), Value::Array(items)) if items.len() == *len => {
+            DynSolValue::FixedArray(
+                items
+                    .iter()
+                    .map(|item| from_json(inner, item))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        (DynSolType::Tuple(types), Value::Array(items)) if items.len() == types.len() => {
+            DynSolValue::Tuple(
+                types
+                    .iter()
+                    .zip(items)
+                    .map(|(ty, item)| from_json(ty, item))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        (_, Value::String(string)) => ty.coerce_str(string)?,
+        (_, Value::Number(_) | Value::Bool(_)) => ty.coerce_str(&value.to_string())?,
+        _ => bail!("cannot convert {value} to {}", ty.sol_type_name()),
+    })
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::datum::DatumWithOid;
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    use serde_json::json;
+
+    #[pg_test]
+    fn abi_decode_dynamic() -> Result<()> {
+        let data = "0000000000000000000000001111111111111111111111111111111111111111000000000000000000000000000000000000000000000000000000000001d688000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000000000000000000000000000000000000000000002dead0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000000000000000000000000003666f6f000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000036261720000000000000000000000000000000000000000000000000000000000";
+
+        let decoded = Spi::get_one_with_args::<pgrx::JsonB>(
+            "SELECT ABI.decode('(address,uint256,bytes,string[])', $1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            decoded.map(|json| json.0),
+            Some(json!([
+                "0x1111111111111111111111111111111111111111",
+                "120456",
+                "0xdead",
+                ["foo", "bar"]
+            ]))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn abi_decode_single() -> Result<()> {
+        let data = "0x000000000000000000000000000000000000000000000000000000000001d688";
+
+        let decoded = Spi::get_one_with_args::<pgrx::JsonB>(
+            "SELECT ABI.decode('uint256', decode(substring($1 from 3), 'hex'));",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(decoded.map(|json| json.0), Some(json!(["120456"])));
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn abi_decode_log_transfer() -> Result<()> {
+        let topics = vec![
+            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            "0x0000000000000000000000001111111111111111111111111111111111111111",
+            "0x0000000000000000000000002222222222222222222222222222222222222222",
+        ];
+        let data = "000000000000000000000000000000000000000000000000000000000001d688";
+
+        let decoded = Spi::get_one_with_args::<pgrx::JsonB>(
+            "SELECT ABI.decode_log('Transfer(address indexed from, address indexed to, uint256 value)', $1, $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            decoded.map(|json| json.0),
+            Some(json!({
+                "from": "0x1111111111111111111111111111111111111111",
+                "to": "0x2222222222222222222222222222222222222222",
+                "value": "120456"
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn abi_decode_log_bytea() -> Result<()> {
+        let decoded = Spi::get_one::<pgrx::JsonB>(
+            "SELECT ABI.decode_log('Transfer(address indexed from, address indexed to, uint256 value)', ARRAY[decode('ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef', 'hex'), decode('0000000000000000000000001111111111111111111111111111111111111111', 'hex'), decode('0000000000000000000000002222222222222222222222222222222222222222', 'hex')], decode('000000000000000000000000000000000000000000000000000000000001d688', 'hex'));",
+        )?;
+
+        assert_eq!(
+            decoded.map(|json| json.0["value"].clone()),
+            Some(json!("120456"))
+        );
+
+        let short = Spi::get_one::<pgrx::JsonB>(
+            "SELECT ABI.try_decode_log('Transfer(address indexed from, address indexed to, uint256 value)', ARRAY[decode('ddf2', 'hex')], decode('00', 'hex'));",
+        )?;
+
+        assert!(short.is_none());
+
+        Ok(())
+    }
+
+    #[pg_test(
+        error = "invalid event signature: expected 0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1, got 0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+    )]
+    fn abi_decode_log_wrong_topic() {
+        Spi::run(
+            "SELECT ABI.decode_log('Sync(uint112 reserve0, uint112 reserve1)', ARRAY['0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'], '00');",
+        )
+        .unwrap();
+    }
+
+    #[pg_test(error = "invalid input syntax for hex: \"0x0g\"")]
+    fn abi_decode_invalid_hex() {
+        Spi::run("SELECT ABI.decode('(uint256)', '0x0g');").unwrap();
+    }
+
+    #[pg_test]
+    fn abi_decode_any_log_registry() -> Result<()> {
+        let erc20 = json!([
+            {
+                "type": "event",
+                "name": "Transfer",
+                "anonymous": false,
+                "inputs": [
+                    { "name": "from", "type": "address", "indexed": true },
+                    { "name": "to", "type": "address", "indexed": true },
+                    { "name": "value", "type": "uint256", "indexed": false }
+                ]
+            },
+            {
+                "type": "function",
+                "name": "transfer",
+                "stateMutability": "nonpayable",
+                "inputs": [
+                    { "name": "to", "type": "address" },
+                    { "name": "amount", "type": "uint256" }
+                ],
+                "outputs": [{ "name": "", "type": "bool" }]
+            }
+        ]);
+
+        let erc721 = json!([
+            {
+                "type": "event",
+                "name": "Transfer",
+                "anonymous": false,
+                "inputs": [
+                    { "name": "from", "type": "address", "indexed": true },
+                    { "name": "to", "type": "address", "indexed": true },
+                    { "name": "tokenId", "type": "uint256", "indexed": true }
+                ]
+            }
+        ]);
+
+        Spi::run_with_args(
+            "SELECT ABI.register('ERC20', $1);",
+            &vec![DatumWithOid::from(pgrx::JsonB(erc20))],
+        )?;
+        Spi::run_with_args(
+            "SELECT ABI.register('ERC721', $1);",
+            &vec![DatumWithOid::from(pgrx::JsonB(erc721))],
+        )?;
+
+        let selector = Spi::get_one::<String>(
+            "SELECT encode(selector, 'hex') FROM ABI.registry WHERE contract = 'ERC20' AND kind = 'function';",
+        )?;
+        assert_eq!(selector, Some(String::from("a9059cbb")));
+
+        let from = "0x0000000000000000000000001111111111111111111111111111111111111111";
+        let to = "0x0000000000000000000000002222222222222222222222222222222222222222";
+        let transfer = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+        let amount = "000000000000000000000000000000000000000000000000000000000001d688";
+
+        let fungible = Spi::get_one_with_args::<pgrx::JsonB>(
+            "SELECT ABI.decode_any_log($1, $2);",
+            &vec![
+                DatumWithOid::from(vec![transfer, from, to]),
+                DatumWithOid::from(amount),
+            ],
+        )?;
+
+        assert_eq!(
+            fungible.map(|json| json.0),
+            Some(json!({
+                "contract": "ERC20",
+                "event": "Transfer",
+                "args": {
+                    "from": "0x1111111111111111111111111111111111111111",
+                    "to": "0x2222222222222222222222222222222222222222",
+                    "value": "120456"
+                }
+            }))
+        );
+
+        let token = format!("0x{amount}");
+        let nft = Spi::get_one_with_args::<pgrx::JsonB>(
+            "SELECT ABI.decode_any_log($1, '');",
+            &vec![DatumWithOid::from(vec![transfer, from, to, token.as_str()])],
+        )?;
+
+        assert_eq!(
+            nft.map(|json| json.0["contract"].clone()),
+            Some(json!("ERC721"))
+        );
+
+        let unknown = Spi::get_one::<pgrx::JsonB>(
+            "SELECT ABI.decode_any_log(ARRAY['0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1'], '');",
+        )?;
+
+        assert!(unknown.is_none());
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn abi_calldata() -> Result<()> {
+        let selector = Spi::get_one::<String>(
+            "SELECT ABI.selector('function transfer(address to, uint256 amount)');",
+        )?;
+
+        assert_eq!(selector, Some(String::from("0xa9059cbb")));
+
+        let input = Spi::get_one::<String>(
+            r#"SELECT ABI.encode_calldata('transfer(address to, uint256 amount)', '{"to": "0x1111111111111111111111111111111111111111", "amount": 120456}');"#,
+        )?;
+
+        assert_eq!(
+            input.as_deref(),
+            Some("0xa9059cbb0000000000000000000000001111111111111111111111111111111111111111000000000000000000000000000000000000000000000000000000000001d688")
+        );
+
+        let decoded = Spi::get_one_with_args::<pgrx::JsonB>(
+            "SELECT ABI.decode_calldata('transfer(address to, uint256 amount)', $1);",
+            &vec![DatumWithOid::from(input)],
+        )?;
+
+        assert_eq!(
+            decoded.map(|json| json.0),
+            Some(json!({
+                "to": "0x1111111111111111111111111111111111111111",
+                "amount": "120456"
+            }))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid function selector: expected 0x095ea7b3, got 0xa9059cbb")]
+    fn abi_decode_calldata_wrong_selector() {
+        Spi::run(
+            "SELECT ABI.decode_calldata('approve(address,uint256)', '0xa9059cbb0000000000000000000000001111111111111111111111111111111111111111000000000000000000000000000000000000000000000000000000000001d688');",
+        )
+        .unwrap();
+    }
+
+    #[pg_test]
+    fn abi_encode_roundtrip() -> Result<()> {
+        let decoded = Spi::get_one::<pgrx::JsonB>(
+            r#"SELECT ABI.decode('(address,uint24,string)', ABI.encode('(address,uint24,string)', '["0x1111111111111111111111111111111111111111", 3000, "foo"]'));"#,
+        )?;
+
+        assert_eq!(
+            decoded.map(|json| json.0),
+            Some(json!([
+                "0x1111111111111111111111111111111111111111",
+                "3000",
+                "foo"
+            ]))
+        );
+
+        let packed = Spi::get_one::<String>(
+            r#"SELECT encode(ABI.encode_packed('(address,uint24,string)', '["0x1111111111111111111111111111111111111111", 3000, "foo"]'), 'hex');"#,
+        )?;
+
+        assert_eq!(
+            packed,
+            Some(String::from(
+                "1111111111111111111111111111111111111111000bb8666f6f"
+            ))
+        );
+
+        let hashed = Spi::get_one::<String>(
+            r#"SELECT H256.keccak256(ABI.encode_packed('string', '["foo"]'));"#,
+        )?;
+
+        assert_eq!(
+            hashed,
+            Some(String::from(
+                "41b1a0649752af1b28b3dc29a1556eee781e4a4c3a1f7f53f90fa834de098c4d"
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn abi_encode_topic() -> Result<()> {
+        let address = Spi::get_one::<String>(
+            "SELECT ABI.encode_topic('address', '0x1111111111111111111111111111111111111111');",
+        )?;
+
+        assert_eq!(
+            address,
+            Some(String::from(
+                "0x0000000000000000000000001111111111111111111111111111111111111111"
+            ))
+        );
+
+        let string = Spi::get_one::<String>("SELECT ABI.encode_topic('string', 'foo');")?;
+
+        assert_eq!(
+            string,
+            Some(String::from(
+                "0x41b1a0649752af1b28b3dc29a1556eee781e4a4c3a1f7f53f90fa834de098c4d"
+            ))
+        );
+
+        Ok(())
+    }
+}
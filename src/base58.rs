@@ -5,11 +5,16 @@ use pgrx::prelude::*;
 mod Base58 {
     use pgrx::prelude::*;
 
+    use crate::decode::{self, OrReport};
+
     #[pg_extern(name = "decode", immutable, parallel_safe)]
     fn decode(string: &str) -> Vec<u8> {
-        bs58::decode(string)
-            .into_vec()
-            .expect("string to be base58")
+        decode::base58(string).or_report()
+    }
+
+    #[pg_extern(name = "try_decode", immutable, parallel_safe)]
+    fn try_decode(string: &str) -> Option<Vec<u8>> {
+        decode::base58(string).ok()
     }
 
     #[pg_extern(name = "encode", immutable, parallel_safe)]
@@ -2,9 +2,10 @@ use pgrx::prelude::*;
 
 use num::{bigint::Sign, BigInt};
 
-use alloy::core::primitives::Address;
+use alloy::core::hex;
+use alloy::core::primitives::{Address, U256};
 
-use anyhow::Result;
+use crate::decode::{self, Error, Result, With};
 
 pub struct Trade {
     pub sell_token: Address,
@@ -18,56 +19,237 @@ pub struct Trade {
 mod Cowswap {
     use pgrx::prelude::*;
 
-    use std::error::Error;
-    use std::str::FromStr;
-
     use alloy::core::hex;
 
-    use super::decode_trade;
+    use super::{decode_trade, into_row, trade_order_uid};
+    use crate::decode::{self, OrReport, Topics};
+
+    extension_sql!(
+        r#"
+CREATE TYPE Cowswap.trade AS (
+    owner text,
+    sell_token text,
+    buy_token text,
+    sell_amount numeric,
+    buy_amount numeric
+);
+"#,
+        name = "cowswap_trade",
+    );
+
+    #[pg_extern(
+        name = "decode_trade",
+        immutable,
+        parallel_safe,
+        requires = ["cowswap_trade"]
+    )]
+    fn cow_decode_trade(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "Cowswap.trade") {
+        let owner = topics.address(1).or_report();
+        let trade = decode_trade(&decode::hex(data).or_report()).or_report();
+
+        into_row(owner, trade)
+    }
+
+    #[pg_extern(
+        name = "decode_trade",
+        immutable,
+        parallel_safe,
+        requires = ["cowswap_trade"]
+    )]
+    fn cow_decode_trade_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "Cowswap.trade") {
+        let owner = topics.address(1).or_report();
+        let trade = decode_trade(data).or_report();
+
+        into_row(owner, trade)
+    }
+
+    #[pg_extern(
+        name = "try_decode_trade",
+        immutable,
+        parallel_safe,
+        requires = ["cowswap_trade"]
+    )]
+    fn cow_try_decode_trade(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Cowswap.trade")> {
+        let owner = topics.address(1).ok()?;
+        let trade = decode_trade(&decode::hex(data).ok()?).ok()?;
+
+        Some(into_row(owner, trade))
+    }
+
+    #[pg_extern(
+        name = "try_decode_trade",
+        immutable,
+        parallel_safe,
+        requires = ["cowswap_trade"]
+    )]
+    fn cow_try_decode_trade_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Cowswap.trade")> {
+        let owner = topics.address(1).ok()?;
+        let trade = decode_trade(data).ok()?;
+
+        Some(into_row(owner, trade))
+    }
+
+    #[pg_extern(name = "trade_owner", immutable, parallel_safe)]
+    fn cow_trade_owner(topics: Array<&str>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "trade_owner_raw", immutable, parallel_safe)]
+    fn cow_trade_owner_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "try_trade_owner", immutable, parallel_safe)]
+    fn cow_try_trade_owner(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_trade_owner_raw", immutable, parallel_safe)]
+    fn cow_try_trade_owner_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "trade_order_uid", immutable, parallel_safe)]
+    fn cow_trade_order_uid(data: &str) -> String {
+        hex::encode(trade_order_uid(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "trade_order_uid", immutable, parallel_safe)]
+    fn cow_trade_order_uid_bytea(data: &[u8]) -> String {
+        hex::encode(trade_order_uid(data).or_report())
+    }
+
+    #[pg_extern(name = "try_trade_order_uid", immutable, parallel_safe)]
+    fn cow_try_trade_order_uid(data: &str) -> Option<String> {
+        trade_order_uid(&decode::hex(data).ok()?)
+            .ok()
+            .map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_trade_order_uid", immutable, parallel_safe)]
+    fn cow_try_trade_order_uid_bytea(data: &[u8]) -> Option<String> {
+        trade_order_uid(data).ok().map(hex::encode)
+    }
 
     #[pg_extern(name = "trade_sell_token", immutable, parallel_safe)]
     fn cow_trade_sell_token(data: &str) -> String {
         hex::encode(
-            decode_trade(&hex::decode(data).unwrap())
-                .unwrap()
+            decode_trade(&decode::hex(data).or_report())
+                .or_report()
                 .sell_token,
         )
     }
 
+    #[pg_extern(name = "trade_sell_token", immutable, parallel_safe)]
+    fn cow_trade_sell_token_bytea(data: &[u8]) -> String {
+        hex::encode(decode_trade(data).or_report().sell_token)
+    }
+
+    #[pg_extern(name = "try_trade_sell_token", immutable, parallel_safe)]
+    fn cow_try_trade_sell_token(data: &str) -> Option<String> {
+        let trade = decode_trade(&decode::hex(data).ok()?).ok()?;
+        Some(hex::encode(trade.sell_token))
+    }
+
+    #[pg_extern(name = "try_trade_sell_token", immutable, parallel_safe)]
+    fn cow_try_trade_sell_token_bytea(data: &[u8]) -> Option<String> {
+        let trade = decode_trade(data).ok()?;
+        Some(hex::encode(trade.sell_token))
+    }
+
     #[pg_extern(name = "trade_buy_token", immutable, parallel_safe)]
     fn cow_trade_buy_token(data: &str) -> String {
-        hex::encode(decode_trade(&hex::decode(data).unwrap()).unwrap().buy_token)
+        hex::encode(
+            decode_trade(&decode::hex(data).or_report())
+                .or_report()
+                .buy_token,
+        )
+    }
+
+    #[pg_extern(name = "trade_buy_token", immutable, parallel_safe)]
+    fn cow_trade_buy_token_bytea(data: &[u8]) -> String {
+        hex::encode(decode_trade(data).or_report().buy_token)
+    }
+
+    #[pg_extern(name = "try_trade_buy_token", immutable, parallel_safe)]
+    fn cow_try_trade_buy_token(data: &str) -> Option<String> {
+        let trade = decode_trade(&decode::hex(data).ok()?).ok()?;
+        Some(hex::encode(trade.buy_token))
+    }
+
+    #[pg_extern(name = "try_trade_buy_token", immutable, parallel_safe)]
+    fn cow_try_trade_buy_token_bytea(data: &[u8]) -> Option<String> {
+        let trade = decode_trade(data).ok()?;
+        Some(hex::encode(trade.buy_token))
     }
 
     #[pg_extern(name = "trade_sell_amount", immutable, parallel_safe)]
-    fn cow_trade_sell_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_trade(&hex::decode(data).unwrap())
-                .unwrap()
-                .sell_amount
-                .to_string()
-                .as_str(),
-        )?)
+    fn cow_trade_sell_amount(data: &str) -> pgrx::AnyNumeric {
+        let trade = decode_trade(&decode::hex(data).or_report()).or_report();
+        decode::numeric(trade.sell_amount)
+    }
+
+    #[pg_extern(name = "trade_sell_amount", immutable, parallel_safe)]
+    fn cow_trade_sell_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let trade = decode_trade(data).or_report();
+        decode::numeric(trade.sell_amount)
+    }
+
+    #[pg_extern(name = "try_trade_sell_amount", immutable, parallel_safe)]
+    fn cow_try_trade_sell_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let trade = decode_trade(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(trade.sell_amount))
+    }
+
+    #[pg_extern(name = "try_trade_sell_amount", immutable, parallel_safe)]
+    fn cow_try_trade_sell_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let trade = decode_trade(data).ok()?;
+        Some(decode::numeric(trade.sell_amount))
+    }
+
+    #[pg_extern(name = "trade_buy_amount", immutable, parallel_safe)]
+    fn cow_trade_buy_amount(data: &str) -> pgrx::AnyNumeric {
+        let trade = decode_trade(&decode::hex(data).or_report()).or_report();
+        decode::numeric(trade.buy_amount)
     }
 
     #[pg_extern(name = "trade_buy_amount", immutable, parallel_safe)]
-    fn cow_trade_buy_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_trade(&hex::decode(data).unwrap())
-                .unwrap()
-                .buy_amount
-                .to_string()
-                .as_str(),
-        )?)
+    fn cow_trade_buy_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let trade = decode_trade(data).or_report();
+        decode::numeric(trade.buy_amount)
+    }
+
+    #[pg_extern(name = "try_trade_buy_amount", immutable, parallel_safe)]
+    fn cow_try_trade_buy_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let trade = decode_trade(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(trade.buy_amount))
+    }
+
+    #[pg_extern(name = "try_trade_buy_amount", immutable, parallel_safe)]
+    fn cow_try_trade_buy_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let trade = decode_trade(data).ok()?;
+        Some(decode::numeric(trade.buy_amount))
     }
 }
 
-fn decode_trade(data: &[u8]) -> Result<Trade> {
-    let sell_token = Address::from_slice(&data[76..96]);
-    let buy_token = Address::from_slice(&data[108..128]);
+pub fn decode_trade(data: &[u8]) -> Result<Trade> {
+    let sell_token = Address::from_slice(decode::slice(data, 76, 96)?);
+    let buy_token = Address::from_slice(decode::slice(data, 108, 128)?);
 
-    let sell_amount = BigInt::from_bytes_be(Sign::Plus, &data[128..160]);
-    let buy_amount = BigInt::from_bytes_be(Sign::Plus, &data[160..192]);
+    let sell_amount = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 128, 160)?);
+    let buy_amount = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 160, 192)?);
 
     Ok(Trade {
         sell_token,
@@ -77,6 +259,33 @@ fn decode_trade(data: &[u8]) -> Result<Trade> {
     })
 }
 
+/// Order UID of the trade (order digest, owner and validTo, 56 bytes), the `bytes` field at the
+/// end of the Trade event. Its offset is relative to the payload which starts after the header.
+fn trade_order_uid(data: &[u8]) -> Result<&[u8]> {
+    let offset = length(decode::slice(data, 224, 256)?)?;
+    let start = offset
+        .checked_add(64)
+        .ok_or_else(|| Error::data("orderUid offset out of range"))?;
+    let length = length(decode::slice(data, start, start.saturating_add(32))?)?;
+
+    decode::slice(data, start + 32, (start + 32).saturating_add(length))
+}
+
+/// Offset or length word of a dynamic field.
+fn length(word: &[u8]) -> Result<usize> {
+    let value = U256::from_be_slice(word);
+    usize::try_from(value).map_err(|_| Error::data(format!("length out of range: {value}")))
+}
+
+fn into_row(owner: Address, trade: Trade) -> decode::Row {
+    decode::row("Cowswap.trade")
+        .with("owner", hex::encode(owner))
+        .with("sell_token", hex::encode(trade.sell_token))
+        .with("buy_token", hex::encode(trade.buy_token))
+        .with("sell_amount", decode::numeric(trade.sell_amount))
+        .with("buy_amount", decode::numeric(trade.buy_amount))
+}
+
 #[cfg(any(test, feature = "pg_test"))]
 #[pg_schema]
 mod tests {
@@ -135,6 +344,67 @@ mod tests {
             Some(pgrx::AnyNumeric::from_str("177487269296317725815438")?)
         );
 
+        let order_uid = Spi::get_one_with_args::<String>(
+            "SELECT Cowswap.trade_order_uid($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            order_uid,
+            Some(String::from("722fccc09b258cb9c62f5e613743c9daaf3f776841e54dfc7e7a6ea682b990a59d0e8cdf137976e03ef92ede4c30648d05e2528566c86ccf"))
+        );
+
+        let short = Spi::get_one::<String>("SELECT Cowswap.try_trade_order_uid('0020');")?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn cow_test_decode_trade() -> Result<()> {
+        let data = "000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000001200000000000000000000000001111111111111111111111111111111111111111000000000000000000000000222222222222222222222222222222222222222200000000000000000000000000000000000000000000000004381cb997a654440000000000000000000000000000000000000000000025959aa97125593a328e000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000000000000000000000000000000000000000000038722fccc09b258cb9c62f5e613743c9daaf3f776841e54dfc7e7a6ea682b990a59d0e8cdf137976e03ef92ede4c30648d05e2528566c86ccf0000000000000000";
+        let topics = "{0xa07a543ab8a018198e99ca0184c93fe9050a79400a0a723441f84de1d972cc17,0x0000000000000000000000003333333333333333333333333333333333333333}";
+
+        let (owner, sell_token, buy_token) = Spi::get_three_with_args::<String, String, String>(
+            "SELECT owner, sell_token, buy_token FROM Cowswap.decode_trade($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            owner,
+            Some(String::from("3333333333333333333333333333333333333333"))
+        );
+        assert_eq!(
+            sell_token,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+        assert_eq!(
+            buy_token,
+            Some(String::from("2222222222222222222222222222222222222222"))
+        );
+
+        let (sell_amount, buy_amount) = Spi::get_two_with_args::<pgrx::AnyNumeric, pgrx::AnyNumeric>(
+            "SELECT sell_amount, buy_amount FROM Cowswap.decode_trade($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            sell_amount,
+            Some(pgrx::AnyNumeric::from_str("304024558286296132")?)
+        );
+        assert_eq!(
+            buy_amount,
+            Some(pgrx::AnyNumeric::from_str("177487269296317725815438")?)
+        );
+
+        let trade_owner = Spi::get_one_with_args::<String>(
+            "SELECT Cowswap.trade_owner($1::text[]);",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        assert_eq!(trade_owner, owner);
+
         Ok(())
     }
 }
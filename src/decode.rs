@@ -0,0 +1,175 @@
+use pgrx::prelude::*;
+
+use alloy::core::hex;
+use alloy::primitives::{Address, B256};
+
+use solana_sdk::pubkey::Pubkey;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Error raised by the decoders on malformed input. It is reported with a proper SQLSTATE and
+/// the `try_` variants of the decoders turn it into NULL instead.
+#[derive(Debug)]
+pub enum Error {
+    /// Input text that is not valid in the expected encoding (hex, base58, ...).
+    InvalidText {
+        expected: &'static str,
+        input: String,
+    },
+    /// Input that is well-formed but does not contain what the decoder expects.
+    Data(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn invalid_text(expected: &'static str, input: &str) -> Self {
+        Error::InvalidText {
+            expected,
+            input: input.to_string(),
+        }
+    }
+
+    pub fn data(message: impl Into<String>) -> Self {
+        Error::Data(message.into())
+    }
+
+    pub fn report(self) -> ! {
+        let code = match self {
+            Error::InvalidText { .. } => PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+            Error::Data(_) => PgSqlErrorCode::ERRCODE_DATA_EXCEPTION,
+        };
+
+        ereport!(ERROR, code, self.to_string());
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Log data can be kilobytes long, the start is enough to find the offending row
+            Error::InvalidText { expected, input } => match input.char_indices().nth(80) {
+                Some((end, _)) => write!(
+                    f,
+                    "invalid input syntax for {expected}: \"{}...\"",
+                    &input[..end]
+                ),
+                None => write!(f, "invalid input syntax for {expected}: \"{input}\""),
+            },
+            Error::Data(message) => f.write_str(message),
+        }
+    }
+}
+
+pub trait OrReport<T> {
+    /// Unwraps the value or raises the error as a Postgres ERROR.
+    fn or_report(self) -> T;
+}
+
+impl<T> OrReport<T> for Result<T> {
+    fn or_report(self) -> T {
+        self.unwrap_or_else(|error| error.report())
+    }
+}
+
+pub type Row = PgHeapTuple<'static, pgrx::pgbox::AllocatedByRust>;
+
+/// Empty row of a composite type created with `extension_sql!` in the schema of the decoder.
+pub fn row(type_name: &str) -> Row {
+    PgHeapTuple::new_composite_type(type_name)
+        .unwrap_or_else(|_| panic!("{type_name} to be a composite type"))
+}
+
+pub trait With {
+    /// Sets an attribute of the row, the attribute has to exist with a matching type.
+    fn with<T: IntoDatum>(self, attribute: &str, value: T) -> Self;
+}
+
+impl With for Row {
+    fn with<T: IntoDatum>(mut self, attribute: &str, value: T) -> Self {
+        self.set_by_name(attribute, value)
+            .unwrap_or_else(|_| panic!("composite type to have a {attribute} attribute"));
+        self
+    }
+}
+
+pub fn hex(input: &str) -> Result<Vec<u8>> {
+    hex::decode(input).map_err(|_| Error::invalid_text("hex", input))
+}
+
+pub fn base58(input: &str) -> Result<Vec<u8>> {
+    bs58::decode(input)
+        .into_vec()
+        .map_err(|_| Error::invalid_text("base58", input))
+}
+
+pub fn h160(input: &str) -> Result<Address> {
+    input
+        .parse()
+        .map_err(|_| Error::invalid_text("h160", input))
+}
+
+pub fn h256(input: &str) -> Result<B256> {
+    input
+        .parse()
+        .map_err(|_| Error::invalid_text("h256", input))
+}
+
+pub fn pubkey(input: &str) -> Result<Pubkey> {
+    Pubkey::from_str(input).map_err(|_| Error::invalid_text("pubkey", input))
+}
+
+/// Returns `data[start..end]` or an error naming how many bytes were expected.
+pub fn slice(data: &[u8], start: usize, end: usize) -> Result<&[u8]> {
+    data.get(start..end).ok_or_else(|| {
+        Error::data(format!(
+            "data too short: expected at least {end} bytes, got {}",
+            data.len()
+        ))
+    })
+}
+
+/// Log topics, either hex text or raw 32 byte words.
+pub trait Topics {
+    /// Returns the topic at `index`, missing and NULL topics are both an error.
+    fn topic(&self, index: usize) -> Result<B256>;
+
+    /// Indexed `address` parameter at `index`, stored in the low 20 bytes of the topic.
+    fn address(&self, index: usize) -> Result<Address> {
+        Ok(Address::from_word(self.topic(index)?))
+    }
+}
+
+impl Topics for Array<'_, &str> {
+    fn topic(&self, index: usize) -> Result<B256> {
+        let topic = self
+            .get(index)
+            .flatten()
+            .ok_or_else(|| missing_topic(index))?;
+        h256(topic)
+    }
+}
+
+impl Topics for Array<'_, &[u8]> {
+    fn topic(&self, index: usize) -> Result<B256> {
+        let topic = self
+            .get(index)
+            .flatten()
+            .ok_or_else(|| missing_topic(index))?;
+        B256::try_from(topic).map_err(|_| {
+            Error::data(format!(
+                "invalid topic {index}: expected 32 bytes, got {}",
+                topic.len()
+            ))
+        })
+    }
+}
+
+fn missing_topic(index: usize) -> Error {
+    Error::data(format!("missing topic {index}"))
+}
+
+pub fn numeric(value: impl ToString) -> pgrx::AnyNumeric {
+    pgrx::AnyNumeric::try_from(value.to_string().as_str()).expect("integer to be valid numeric")
+}
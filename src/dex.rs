@@ -0,0 +1,303 @@
+use pgrx::prelude::*;
+
+use alloy::core::hex;
+use alloy::primitives::Address;
+use bigdecimal::BigDecimal;
+use num::{bigint::Sign, BigInt, Signed, Zero};
+
+use crate::decode::{self, Error, Result, Topics, With};
+use crate::{cowswap, sushiswap, uniswap, uniswap_v4, velodrome};
+
+/// One swap of any supported protocol, `action` is 1 (BUY) or -1 (SELL) like `swap_type`.
+pub struct Swap {
+    pub sender: Address,
+    pub recipient: Option<Address>,
+    pub action: i32,
+    pub base_amount: BigInt,
+    pub quote_amount: BigInt,
+    pub price: Option<BigDecimal>,
+}
+
+#[pg_schema]
+#[allow(non_snake_case)]
+mod DEX {
+    use pgrx::prelude::*;
+
+    use super::{decode_swap, into_row};
+    use crate::decode::{self, OrReport};
+
+    extension_sql!(
+        r#"
+CREATE TYPE DEX.swap AS (
+    protocol text,
+    sender text,
+    recipient text,
+    action integer,
+    base_amount numeric,
+    quote_amount numeric,
+    price numeric
+);
+"#,
+        name = "dex_swap",
+    );
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["dex_swap"])]
+    fn dex_decode_swap(
+        protocol: &str,
+        topics: Array<&str>,
+        data: &str,
+        base_decimals: default!(Option<i64>, "NULL"),
+        quote_decimals: default!(Option<i64>, "NULL"),
+    ) -> pgrx::composite_type!('static, "DEX.swap") {
+        let data = decode::hex(data).or_report();
+        let swap = decode_swap(protocol, &topics, &data, base_decimals, quote_decimals).or_report();
+
+        into_row(protocol, swap)
+    }
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["dex_swap"])]
+    fn dex_decode_swap_bytea(
+        protocol: &str,
+        topics: Array<&[u8]>,
+        data: &[u8],
+        base_decimals: default!(Option<i64>, "NULL"),
+        quote_decimals: default!(Option<i64>, "NULL"),
+    ) -> pgrx::composite_type!('static, "DEX.swap") {
+        let swap = decode_swap(protocol, &topics, data, base_decimals, quote_decimals).or_report();
+
+        into_row(protocol, swap)
+    }
+
+    #[pg_extern(name = "try_decode_swap", immutable, parallel_safe, requires = ["dex_swap"])]
+    fn dex_try_decode_swap(
+        protocol: &str,
+        topics: Array<&str>,
+        data: &str,
+        base_decimals: default!(Option<i64>, "NULL"),
+        quote_decimals: default!(Option<i64>, "NULL"),
+    ) -> Option<pgrx::composite_type!('static, "DEX.swap")> {
+        let data = decode::hex(data).ok()?;
+        let swap = decode_swap(protocol, &topics, &data, base_decimals, quote_decimals).ok()?;
+
+        Some(into_row(protocol, swap))
+    }
+
+    #[pg_extern(name = "try_decode_swap", immutable, parallel_safe, requires = ["dex_swap"])]
+    fn dex_try_decode_swap_bytea(
+        protocol: &str,
+        topics: Array<&[u8]>,
+        data: &[u8],
+        base_decimals: default!(Option<i64>, "NULL"),
+        quote_decimals: default!(Option<i64>, "NULL"),
+    ) -> Option<pgrx::composite_type!('static, "DEX.swap")> {
+        let swap = decode_swap(protocol, &topics, data, base_decimals, quote_decimals).ok()?;
+
+        Some(into_row(protocol, swap))
+    }
+}
+
+fn into_row(protocol: &str, swap: Swap) -> decode::Row {
+    decode::row("DEX.swap")
+        .with("protocol", protocol)
+        .with("sender", hex::encode(swap.sender))
+        .with("recipient", swap.recipient.map(hex::encode))
+        .with("action", swap.action)
+        .with("base_amount", decode::numeric(swap.base_amount))
+        .with("quote_amount", decode::numeric(swap.quote_amount))
+        .with("price", swap.price.map(decode::numeric))
+}
+
+/// Decodes a swap log of `protocol`, the price needs both decimals and is NULL otherwise.
+fn decode_swap(
+    protocol: &str,
+    topics: &impl Topics,
+    data: &[u8],
+    base_decimals: Option<i64>,
+    quote_decimals: Option<i64>,
+) -> Result<Swap> {
+    let decimals = base_decimals.zip(quote_decimals);
+
+    match protocol {
+        "uniswap_v2" | "sushiswap" => {
+            let swap = sushiswap::decode_swap(data)?;
+            Ok(Swap {
+                sender: topics.address(1)?,
+                recipient: Some(topics.address(2)?),
+                action: swap.action as i32,
+                price: decimals.and_then(|decimals| {
+                    amounts_price(&swap.base_amount, &swap.quote_amount, decimals)
+                }),
+                base_amount: swap.base_amount,
+                quote_amount: swap.quote_amount,
+            })
+        }
+        "velodrome" => {
+            let swap = velodrome::decode_swap(data)?;
+            Ok(Swap {
+                sender: topics.address(1)?,
+                recipient: Some(topics.address(2)?),
+                action: swap.action as i32,
+                price: decimals.and_then(|decimals| {
+                    amounts_price(&swap.base_amount, &swap.quote_amount, decimals)
+                }),
+                base_amount: swap.base_amount,
+                quote_amount: swap.quote_amount,
+            })
+        }
+        "uniswap_v3" => {
+            let swap = uniswap::decode_swap(data)?;
+            let sqrt = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 128, 160)?);
+            Ok(Swap {
+                sender: topics.address(1)?,
+                recipient: Some(topics.address(2)?),
+                action: swap.action as i32,
+                base_amount: swap.base_amount.abs(),
+                quote_amount: swap.quote_amount.abs(),
+                price: decimals.map(|(base_decimals, quote_decimals)| {
+                    uniswap::sqrt_price_to_price(sqrt, base_decimals, quote_decimals)
+                }),
+            })
+        }
+        "uniswap_v4" => {
+            let swap = uniswap_v4::decode_swap(data)?;
+            Ok(Swap {
+                sender: topics.address(2)?,
+                recipient: None,
+                action: swap.action as i32,
+                base_amount: swap.amount0.abs(),
+                quote_amount: swap.amount1.abs(),
+                price: decimals.map(|(base_decimals, quote_decimals)| {
+                    uniswap::sqrt_price_to_price(swap.sqrt_price_x96, base_decimals, quote_decimals)
+                }),
+            })
+        }
+        "cowswap" => {
+            // The trade owner is the sender, the receiver of the order is not part of the event
+            let trade = cowswap::decode_trade(data)?;
+            Ok(Swap {
+                sender: topics.address(1)?,
+                recipient: None,
+                action: uniswap::SwapAction::SELL as i32,
+                price: decimals.and_then(|decimals| {
+                    amounts_price(&trade.sell_amount, &trade.buy_amount, decimals)
+                }),
+                base_amount: trade.sell_amount,
+                quote_amount: trade.buy_amount,
+            })
+        }
+        _ => Err(Error::invalid_text("protocol", protocol)),
+    }
+}
+
+/// Execution price of a swap from its amounts, NULL when nothing of the base token moved.
+fn amounts_price(
+    base_amount: &BigInt,
+    quote_amount: &BigInt,
+    (base_decimals, quote_decimals): (i64, i64),
+) -> Option<BigDecimal> {
+    if base_amount.is_zero() {
+        return None;
+    }
+
+    let base = BigDecimal::new(base_amount.clone(), base_decimals);
+    let quote = BigDecimal::new(quote_amount.clone(), quote_decimals);
+
+    Some((quote / base).round(quote_decimals))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::datum::DatumWithOid;
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn dex_test_decode_swap_uniswap_v3() -> Result<()> {
+        let topics = "{0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67,0x000000000000000000000000e592427a0aece92de3edee1f18e0157c05861564,0x0000000000000000000000001111111111111111111111111111111111111111}";
+        let data = "000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000a00000000000000000000000000000000000000000000069e3a94cbdc95782d980fffffffffffffffffffffffffffffffffffffffffffffffffca2be462fef64520000000000000000000000000000000000000000002dd9e533e8a406c1663add00000000000000000000000000000000000000000000ac695d7b1db89e7cd0ddfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffdc865";
+
+        let (sender, recipient, action) = Spi::get_three_with_args::<String, String, i32>(
+            "SELECT sender, recipient, action FROM DEX.decode_swap('uniswap_v3', $1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            sender,
+            Some(String::from("e592427a0aece92de3edee1f18e0157c05861564"))
+        );
+        assert_eq!(
+            recipient,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+        assert_eq!(action, Some(-1));
+
+        let (base_amount, quote_amount, price) =
+            Spi::get_three_with_args::<pgrx::AnyNumeric, pgrx::AnyNumeric, pgrx::AnyNumeric>(
+                "SELECT base_amount, quote_amount, price FROM DEX.decode_swap('uniswap_v3', $1::text[], $2, 18, 18);",
+                &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+            )?;
+
+        assert_eq!(
+            base_amount,
+            Some(pgrx::AnyNumeric::from_str("500048090940207909755264")?)
+        );
+        assert_eq!(
+            quote_amount,
+            Some(pgrx::AnyNumeric::from_str("242422221263379374")?)
+        );
+        assert_eq!(
+            price,
+            Some(pgrx::AnyNumeric::from_str("0.000000489486448249")?)
+        );
+
+        // Without decimals there is no price
+        let price = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT price FROM DEX.decode_swap('uniswap_v3', $1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(price, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn dex_test_decode_swap_sushiswap() -> Result<()> {
+        let topics = "{0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822,0x000000000000000000000000d9e1ce17f2641f24ae83637ab66a2cca9c378b9f,0x0000000000000000000000001111111111111111111111111111111111111111}";
+        let data = "00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000080000000000000000000000000000000000000000000000000000aa87bee5380000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000006c6363e4d3aa68afbe";
+
+        let (protocol, base_amount, price) =
+            Spi::get_three_with_args::<String, pgrx::AnyNumeric, pgrx::AnyNumeric>(
+                "SELECT protocol, base_amount, price FROM DEX.decode_swap('sushiswap', $1::text[], $2, 18, 18);",
+                &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+            )?;
+
+        assert_eq!(protocol, Some(String::from("sushiswap")));
+        assert_eq!(
+            base_amount,
+            Some(pgrx::AnyNumeric::from_str("3000000000000000")?)
+        );
+        assert_eq!(
+            price,
+            Some(pgrx::AnyNumeric::from_str("666470.059796943004991333")?)
+        );
+
+        let missing = Spi::get_one_with_args::<String>(
+            "SELECT protocol FROM DEX.try_decode_swap('sushiswap', '{}'::text[], $1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid input syntax for protocol: \"curve\"")]
+    fn dex_test_decode_swap_unknown_protocol() {
+        Spi::run("SELECT DEX.decode_swap('curve', '{}'::text[], '00');").unwrap();
+    }
+}
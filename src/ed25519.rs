@@ -5,14 +5,22 @@ use pgrx::prelude::*;
 mod ED25519 {
     use pgrx::prelude::*;
 
-    use solana_sdk::pubkey::Pubkey;
-    use std::str::FromStr;
+    use crate::decode::{self, OrReport};
+    use crate::types;
 
     #[pg_extern(name = "on_curve", immutable, parallel_safe)]
     fn on_curve(address: &str) -> bool {
-        Pubkey::from_str(address)
-            .expect("to be valid key")
-            .is_on_curve()
+        decode::pubkey(address).or_report().is_on_curve()
+    }
+
+    #[pg_extern(name = "try_on_curve", immutable, parallel_safe)]
+    fn try_on_curve(address: &str) -> Option<bool> {
+        decode::pubkey(address).ok().map(|key| key.is_on_curve())
+    }
+
+    #[pg_extern(name = "is_on_curve", immutable, parallel_safe)]
+    fn is_on_curve(key: types::Pubkey) -> bool {
+        key.0.is_on_curve()
     }
 }
 
@@ -44,6 +52,12 @@ mod tests {
 
         assert_eq!(not_on_curve, Some(false));
 
+        let native = Spi::get_two::<bool, bool>(
+            "SELECT ED25519.is_on_curve('7dGrdJRYtsNR8UYxZ3TnifXGjGc9eRYLq9sELwYpuuUu'::pubkey), ED25519.is_on_curve('5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1'::pubkey);",
+        )?;
+
+        assert_eq!(native, (Some(true), Some(false)));
+
         Ok(())
     }
 }
@@ -1,56 +1,271 @@
 use pgrx::prelude::*;
 
+use alloy::core::hex;
 use alloy::primitives::{Address, U256};
 
-use anyhow::Result;
+use crate::decode::{self, Result, Topics, With};
 
 #[pg_schema]
 #[allow(non_snake_case)]
 mod ERC20 {
     use alloy::core::hex;
-    use alloy::primitives::{Address, FixedBytes, U256};
 
     use pgrx::prelude::*;
 
+    use super::{decode_transfer, into_row, transfer_value};
+    use crate::decode::{self, OrReport, Topics};
+
+    extension_sql!(
+        r#"
+CREATE TYPE ERC20.transfer AS (
+    from_address text,
+    to_address text,
+    value numeric
+);
+"#,
+        name = "erc20_transfer",
+    );
+
+    #[pg_extern(
+        name = "decode_transfer",
+        immutable,
+        parallel_safe,
+        requires = ["erc20_transfer"]
+    )]
+    fn erc20_decode_transfer(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "ERC20.transfer") {
+        into_row(decode_transfer(&topics, &decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(
+        name = "decode_transfer",
+        immutable,
+        parallel_safe,
+        requires = ["erc20_transfer"]
+    )]
+    fn erc20_decode_transfer_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "ERC20.transfer") {
+        into_row(decode_transfer(&topics, data).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_transfer",
+        immutable,
+        parallel_safe,
+        requires = ["erc20_transfer"]
+    )]
+    fn erc20_try_decode_transfer(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "ERC20.transfer")> {
+        decode_transfer(&topics, &decode::hex(data).ok()?)
+            .ok()
+            .map(into_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_transfer",
+        immutable,
+        parallel_safe,
+        requires = ["erc20_transfer"]
+    )]
+    fn erc20_try_decode_transfer_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "ERC20.transfer")> {
+        decode_transfer(&topics, data).ok().map(into_row)
+    }
+
     #[pg_extern(name = "transfer_from", immutable, parallel_safe)]
     fn erc20_transfer_from(topics: Array<&str>) -> String {
-        let t1 = topics.get(1).expect("Invalid topics");
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "transfer_from_raw", immutable, parallel_safe)]
+    fn erc20_transfer_from_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "try_transfer_from", immutable, parallel_safe)]
+    fn erc20_try_transfer_from(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
 
-        let h256: FixedBytes<32> = t1.unwrap().parse().expect("Failed to parse H256");
-        hex::encode(Address::from_word(h256))
+    #[pg_extern(name = "try_transfer_from_raw", immutable, parallel_safe)]
+    fn erc20_try_transfer_from_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
     }
 
     #[pg_extern(name = "transfer_to", immutable, parallel_safe)]
     fn erc20_transfer_to(topics: Array<&str>) -> String {
-        let t2 = topics.get(2).expect("Invalid topics");
+        hex::encode(topics.address(2).or_report())
+    }
 
-        let h256: FixedBytes<32> = t2.unwrap().parse().expect("Failed to parse H256");
-        hex::encode(Address::from_word(h256))
+    #[pg_extern(name = "transfer_to_raw", immutable, parallel_safe)]
+    fn erc20_transfer_to_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(2).or_report())
     }
 
-    #[pg_extern(immutable, parallel_safe)]
-    fn transfer_value(data: &str) -> pgrx::AnyNumeric {
-        pgrx::AnyNumeric::try_from(
-            U256::from_be_slice(&hex::decode(data).unwrap()[64..96])
-                .to_string()
-                .as_str(),
-        )
-        .expect("Failed to convert U256 to AnyNumeric")
+    #[pg_extern(name = "try_transfer_to", immutable, parallel_safe)]
+    fn erc20_try_transfer_to(topics: Array<&str>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_transfer_to_raw", immutable, parallel_safe)]
+    fn erc20_try_transfer_to_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "transfer_value", immutable, parallel_safe)]
+    fn erc20_transfer_value(data: &str) -> pgrx::AnyNumeric {
+        decode::numeric(transfer_value(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "transfer_value", immutable, parallel_safe)]
+    fn erc20_transfer_value_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        decode::numeric(transfer_value(data).or_report())
+    }
+
+    #[pg_extern(name = "try_transfer_value", immutable, parallel_safe)]
+    fn erc20_try_transfer_value(data: &str) -> Option<pgrx::AnyNumeric> {
+        transfer_value(&decode::hex(data).ok()?)
+            .ok()
+            .map(decode::numeric)
+    }
+
+    #[pg_extern(name = "try_transfer_value", immutable, parallel_safe)]
+    fn erc20_try_transfer_value_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        transfer_value(data).ok().map(decode::numeric)
     }
 }
 
-#[allow(dead_code)]
 pub struct Transfer {
     pub from: Address,
     pub to: Address,
     pub value: U256,
 }
 
-#[allow(dead_code)]
-fn decode_transfer(topics: Vec<Option<&str>>, data: &[u8]) -> Result<Transfer> {
+fn decode_transfer(topics: &impl Topics, data: &[u8]) -> Result<Transfer> {
     Ok(Transfer {
-        from: topics[1].unwrap().parse::<Address>()?,
-        to: topics[2].unwrap().parse::<Address>()?,
-        value: U256::from_be_slice(&data[64..96]),
+        from: topics.address(1)?,
+        to: topics.address(2)?,
+        value: transfer_value(data)?,
     })
 }
+
+fn into_row(transfer: Transfer) -> decode::Row {
+    decode::row("ERC20.transfer")
+        .with("from_address", hex::encode(transfer.from))
+        .with("to_address", hex::encode(transfer.to))
+        .with("value", decode::numeric(transfer.value))
+}
+
+fn transfer_value(data: &[u8]) -> Result<U256> {
+    Ok(U256::from_be_slice(decode::slice(data, 64, 96)?))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn erc20_test_try_transfer() -> Result<()> {
+        let value = Spi::get_one::<pgrx::AnyNumeric>(
+            "SELECT ERC20.try_transfer_value('00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000001d688');",
+        )?;
+
+        assert_eq!(value, Some(pgrx::AnyNumeric::from_str("120456")?));
+
+        let short = Spi::get_one::<pgrx::AnyNumeric>("SELECT ERC20.try_transfer_value('0020');")?;
+
+        assert_eq!(short, None);
+
+        let missing = Spi::get_one::<String>(
+            "SELECT ERC20.try_transfer_to('{0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef}');",
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn erc20_test_decode_transfer() -> Result<()> {
+        let (from_address, to_address, value) =
+            Spi::get_three::<String, String, pgrx::AnyNumeric>(
+                "SELECT from_address, to_address, value FROM ERC20.decode_transfer('{0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef,0x0000000000000000000000001111111111111111111111111111111111111111,0x0000000000000000000000002222222222222222222222222222222222222222}', '00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000001d688');",
+            )?;
+
+        assert_eq!(
+            from_address,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+        assert_eq!(
+            to_address,
+            Some(String::from("2222222222222222222222222222222222222222"))
+        );
+        assert_eq!(value, Some(pgrx::AnyNumeric::from_str("120456")?));
+
+        let missing = Spi::get_one::<String>(
+            "SELECT from_address FROM ERC20.try_decode_transfer('{0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef}', '0020');",
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn erc20_test_decode_transfer_bytea() -> Result<()> {
+        let (to_address, value) = Spi::get_two::<String, pgrx::AnyNumeric>(
+            "SELECT to_address, value FROM ERC20.decode_transfer(ARRAY[decode('ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef', 'hex'), decode('0000000000000000000000001111111111111111111111111111111111111111', 'hex'), decode('0000000000000000000000002222222222222222222222222222222222222222', 'hex')], decode('00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000001d688', 'hex'));",
+        )?;
+
+        assert_eq!(
+            to_address,
+            Some(String::from("2222222222222222222222222222222222222222"))
+        );
+        assert_eq!(value, Some(pgrx::AnyNumeric::from_str("120456")?));
+
+        let from_address = Spi::get_one::<String>(
+            "SELECT ERC20.transfer_from_raw(ARRAY[decode('ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef', 'hex'), decode('0000000000000000000000001111111111111111111111111111111111111111', 'hex')]);",
+        )?;
+
+        assert_eq!(
+            from_address,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+
+        let value = Spi::get_one::<pgrx::AnyNumeric>(
+            "SELECT ERC20.transfer_value(decode('00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000001d688', 'hex'));",
+        )?;
+
+        assert_eq!(value, Some(pgrx::AnyNumeric::from_str("120456")?));
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid topic 1: expected 32 bytes, got 20")]
+    fn erc20_test_decode_transfer_short_topic() {
+        Spi::run("SELECT ERC20.decode_transfer(ARRAY[decode('ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef', 'hex'), decode('1111111111111111111111111111111111111111', 'hex')], decode('00', 'hex'));").unwrap();
+    }
+
+    #[pg_test(error = "data too short: expected at least 96 bytes, got 2")]
+    fn erc20_test_transfer_value_short() {
+        Spi::run("SELECT ERC20.transfer_value('0020');").unwrap();
+    }
+
+    #[pg_test(error = "invalid input syntax for hex: \"not hex\"")]
+    fn erc20_test_transfer_value_invalid() {
+        Spi::run("SELECT ERC20.transfer_value('not hex');").unwrap();
+    }
+}
@@ -1,54 +1,223 @@
 use pgrx::prelude::*;
 
+use alloy::core::hex;
 use alloy::primitives::{Address, U256};
 
-use anyhow::Result;
+use crate::decode::{self, Result, Topics, With};
 
 #[pg_schema]
 #[allow(non_snake_case)]
 mod ERC721 {
     use alloy::core::hex;
-    use alloy::primitives::{Address, FixedBytes, U256};
 
     use pgrx::prelude::*;
 
+    use super::{decode_transfer, into_row, transfer_token};
+    use crate::decode::{self, OrReport, Topics};
+
+    extension_sql!(
+        r#"
+CREATE TYPE ERC721.transfer AS (
+    from_address text,
+    to_address text,
+    token_id numeric
+);
+"#,
+        name = "erc721_transfer",
+    );
+
+    #[pg_extern(
+        name = "decode_transfer",
+        immutable,
+        parallel_safe,
+        requires = ["erc721_transfer"]
+    )]
+    fn erc721_decode_transfer(
+        topics: Array<&str>,
+    ) -> pgrx::composite_type!('static, "ERC721.transfer") {
+        into_row(decode_transfer(&topics).or_report())
+    }
+
+    #[pg_extern(
+        name = "decode_transfer_raw",
+        immutable,
+        parallel_safe,
+        requires = ["erc721_transfer"]
+    )]
+    fn erc721_decode_transfer_raw(
+        topics: Array<&[u8]>,
+    ) -> pgrx::composite_type!('static, "ERC721.transfer") {
+        into_row(decode_transfer(&topics).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_transfer",
+        immutable,
+        parallel_safe,
+        requires = ["erc721_transfer"]
+    )]
+    fn erc721_try_decode_transfer(
+        topics: Array<&str>,
+    ) -> Option<pgrx::composite_type!('static, "ERC721.transfer")> {
+        decode_transfer(&topics).ok().map(into_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_transfer_raw",
+        immutable,
+        parallel_safe,
+        requires = ["erc721_transfer"]
+    )]
+    fn erc721_try_decode_transfer_raw(
+        topics: Array<&[u8]>,
+    ) -> Option<pgrx::composite_type!('static, "ERC721.transfer")> {
+        decode_transfer(&topics).ok().map(into_row)
+    }
+
     #[pg_extern(name = "transfer_from", immutable, parallel_safe)]
     fn erc721_transfer_from(topics: Array<&str>) -> String {
-        let t1 = topics.get(1).expect("Invalid topics");
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "transfer_from_raw", immutable, parallel_safe)]
+    fn erc721_transfer_from_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
 
-        let h256: FixedBytes<32> = t1.unwrap().parse().expect("Failed to parse H256");
-        hex::encode(Address::from_word(h256))
+    #[pg_extern(name = "try_transfer_from", immutable, parallel_safe)]
+    fn erc721_try_transfer_from(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_transfer_from_raw", immutable, parallel_safe)]
+    fn erc721_try_transfer_from_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
     }
 
     #[pg_extern(name = "transfer_to", immutable, parallel_safe)]
     fn erc721_transfer_to(topics: Array<&str>) -> String {
-        let t2 = topics.get(2).expect("Invalid topics");
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "transfer_to_raw", immutable, parallel_safe)]
+    fn erc721_transfer_to_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "try_transfer_to", immutable, parallel_safe)]
+    fn erc721_try_transfer_to(topics: Array<&str>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_transfer_to_raw", immutable, parallel_safe)]
+    fn erc721_try_transfer_to_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
 
-        let h256: FixedBytes<32> = t2.unwrap().parse().expect("Failed to parse H256");
-        hex::encode(Address::from_word(h256))
+    #[pg_extern(name = "transfer_token", immutable, parallel_safe)]
+    fn erc721_transfer_token(topics: Array<&str>) -> pgrx::AnyNumeric {
+        decode::numeric(transfer_token(&topics).or_report())
     }
 
-    #[pg_extern(immutable, parallel_safe)]
-    fn transfer_token(topics: Array<&str>) -> pgrx::AnyNumeric {
-        let t3 = topics.get(3).expect("Invalid topics");
+    #[pg_extern(name = "transfer_token_raw", immutable, parallel_safe)]
+    fn erc721_transfer_token_raw(topics: Array<&[u8]>) -> pgrx::AnyNumeric {
+        decode::numeric(transfer_token(&topics).or_report())
+    }
+
+    #[pg_extern(name = "try_transfer_token", immutable, parallel_safe)]
+    fn erc721_try_transfer_token(topics: Array<&str>) -> Option<pgrx::AnyNumeric> {
+        transfer_token(&topics).ok().map(decode::numeric)
+    }
 
-        pgrx::AnyNumeric::try_from(t3.unwrap().parse::<U256>().unwrap().to_string().as_str())
-            .expect("Failed to convert U256 to AnyNumeric")
+    #[pg_extern(name = "try_transfer_token_raw", immutable, parallel_safe)]
+    fn erc721_try_transfer_token_raw(topics: Array<&[u8]>) -> Option<pgrx::AnyNumeric> {
+        transfer_token(&topics).ok().map(decode::numeric)
     }
 }
 
-#[allow(dead_code)]
 pub struct Transfer {
     pub from: Address,
     pub to: Address,
     pub token_id: U256,
 }
 
-#[allow(dead_code)]
-fn decode_transfer(topics: Vec<Option<&str>>, data: &[u8]) -> Result<Transfer> {
+fn decode_transfer(topics: &impl Topics) -> Result<Transfer> {
     Ok(Transfer {
-        from: topics[1].unwrap().parse::<Address>()?,
-        to: topics[2].unwrap().parse::<Address>()?,
-        token_id: U256::from_be_slice(&data[64..96]),
+        from: topics.address(1)?,
+        to: topics.address(2)?,
+        token_id: transfer_token(topics)?,
     })
 }
+
+fn into_row(transfer: Transfer) -> decode::Row {
+    decode::row("ERC721.transfer")
+        .with("from_address", hex::encode(transfer.from))
+        .with("to_address", hex::encode(transfer.to))
+        .with("token_id", decode::numeric(transfer.token_id))
+}
+
+fn transfer_token(topics: &impl Topics) -> Result<U256> {
+    Ok(U256::from_be_bytes(topics.topic(3)?.0))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn erc721_test_decode_transfer() -> Result<()> {
+        let (from_address, to_address, token_id) =
+            Spi::get_three::<String, String, pgrx::AnyNumeric>(
+                "SELECT from_address, to_address, token_id FROM ERC721.decode_transfer('{0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef,0x0000000000000000000000001111111111111111111111111111111111111111,0x0000000000000000000000002222222222222222222222222222222222222222,0x000000000000000000000000000000000000000000000000000000000001d688}');",
+            )?;
+
+        assert_eq!(
+            from_address,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+        assert_eq!(
+            to_address,
+            Some(String::from("2222222222222222222222222222222222222222"))
+        );
+        assert_eq!(token_id, Some(pgrx::AnyNumeric::from_str("120456")?));
+
+        let missing = Spi::get_one::<String>(
+            "SELECT from_address FROM ERC721.try_decode_transfer('{0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef,0x0000000000000000000000001111111111111111111111111111111111111111,0x0000000000000000000000002222222222222222222222222222222222222222}');",
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn erc721_test_decode_transfer_raw() -> Result<()> {
+        let (to_address, token_id) = Spi::get_two::<String, pgrx::AnyNumeric>(
+            "SELECT to_address, token_id FROM ERC721.decode_transfer_raw(ARRAY[decode('ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef', 'hex'), decode('0000000000000000000000001111111111111111111111111111111111111111', 'hex'), decode('0000000000000000000000002222222222222222222222222222222222222222', 'hex'), decode('000000000000000000000000000000000000000000000000000000000001d688', 'hex')]);",
+        )?;
+
+        assert_eq!(
+            to_address,
+            Some(String::from("2222222222222222222222222222222222222222"))
+        );
+        assert_eq!(token_id, Some(pgrx::AnyNumeric::from_str("120456")?));
+
+        let short = Spi::get_one::<String>(
+            "SELECT ERC721.try_transfer_from_raw(ARRAY[decode('ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef', 'hex'), decode('1111111111111111111111111111111111111111', 'hex')]);",
+        )?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "missing topic 3")]
+    fn erc721_test_decode_transfer_missing_token() {
+        Spi::run("SELECT ERC721.decode_transfer('{0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef,0x0000000000000000000000001111111111111111111111111111111111111111,0x0000000000000000000000002222222222222222222222222222222222222222}');").unwrap();
+    }
+}
@@ -1,22 +1,130 @@
 use pgrx::prelude::*;
 
+use alloy::primitives::Address;
+
+use std::cmp::Ordering;
+
+use crate::decode::{self, Error, Result};
+
 #[pg_schema]
 #[allow(non_snake_case)]
 mod H160 {
     use alloy::core::hex;
-    use alloy::primitives::{Address, FixedBytes};
+    use alloy::primitives::Address;
 
     use pgrx::prelude::*;
 
+    use super::{parse_checksummed, parse_strict};
+    use crate::decode::{self, OrReport};
+
     #[pg_extern(name = "parse", immutable, parallel_safe)]
     fn parse_h160(h160: &str) -> String {
-        hex::encode(h160.parse::<Address>().expect("Failed to parse H160"))
+        hex::encode(decode::h160(h160).or_report())
+    }
+
+    #[pg_extern(name = "try_parse", immutable, parallel_safe)]
+    fn try_parse_h160(h160: &str) -> Option<String> {
+        decode::h160(h160).ok().map(hex::encode)
     }
 
     #[pg_extern(immutable, parallel_safe)]
     fn from_h256(h256: &str) -> String {
-        let h256: FixedBytes<32> = h256.parse().expect("Failed to parse H256");
-        hex::encode(Address::from_word(h256))
+        hex::encode(Address::from_word(decode::h256(h256).or_report()))
+    }
+
+    #[pg_extern(immutable, parallel_safe)]
+    fn try_from_h256(h256: &str) -> Option<String> {
+        decode::h256(h256)
+            .ok()
+            .map(|h256| hex::encode(Address::from_word(h256)))
+    }
+
+    #[pg_extern(immutable, parallel_safe)]
+    fn create_address(deployer: &str, nonce: i64) -> String {
+        let deployer = decode::h160(deployer).or_report();
+        let nonce = u64::try_from(nonce)
+            .map_err(|_| decode::Error::data(format!("invalid nonce: {nonce}")))
+            .or_report();
+
+        hex::encode(deployer.create(nonce))
+    }
+
+    #[pg_extern(immutable, parallel_safe)]
+    fn create2_address(deployer: &str, salt: &str, init_code_hash: &str) -> String {
+        let deployer = decode::h160(deployer).or_report();
+        let salt = decode::h256(salt).or_report();
+        let init_code_hash = decode::h256(init_code_hash).or_report();
+
+        hex::encode(deployer.create2(salt, init_code_hash))
+    }
+
+    #[pg_extern(name = "parse_strict", immutable, parallel_safe)]
+    fn parse_strict_h160(h160: &str, chain_id: default!(Option<i64>, "NULL")) -> String {
+        hex::encode(parse_strict(h160, chain_id).or_report())
+    }
+
+    #[pg_extern(name = "try_parse_strict", immutable, parallel_safe)]
+    fn try_parse_strict_h160(
+        h160: &str,
+        chain_id: default!(Option<i64>, "NULL"),
+    ) -> Option<String> {
+        parse_strict(h160, chain_id).ok().map(hex::encode)
+    }
+
+    #[pg_extern(immutable, parallel_safe)]
+    fn to_checksum(h160: &str, chain_id: default!(Option<i64>, "NULL")) -> String {
+        let address = decode::h160(h160).or_report();
+        address.to_checksum(super::chain_id(chain_id).or_report())
+    }
+
+    #[pg_extern(immutable, parallel_safe)]
+    fn is_checksum_valid(h160: &str, chain_id: default!(Option<i64>, "NULL")) -> bool {
+        parse_checksummed(h160, chain_id).is_ok()
+    }
+}
+
+/// EIP-1191 chain id, NULL means plain EIP-55.
+fn chain_id(chain_id: Option<i64>) -> Result<Option<u64>> {
+    chain_id
+        .map(|id| u64::try_from(id).map_err(|_| Error::data(format!("invalid chain id: {id}"))))
+        .transpose()
+}
+
+/// Parses an address that has to carry a valid EIP-55 (or EIP-1191 with `chain_id`) checksum.
+fn parse_checksummed(input: &str, chain_id: Option<i64>) -> Result<Address> {
+    let chain_id = self::chain_id(chain_id)?;
+    let prefixed = match input.starts_with("0x") {
+        true => input.to_string(),
+        false => format!("0x{input}"),
+    };
+
+    Address::parse_checksummed(prefixed, chain_id)
+        .map_err(|_| Error::invalid_text("checksummed h160", input))
+}
+
+/// Same as `H160.parse` but mixed-case input is rejected unless its checksum is valid, all
+/// lowercase or all uppercase input carries no checksum and is accepted as is.
+fn parse_strict(input: &str, chain_id: Option<i64>) -> Result<Address> {
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+
+    let lowercase = !digits.chars().any(|c| c.is_ascii_uppercase());
+    let uppercase = !digits.chars().any(|c| c.is_ascii_lowercase());
+
+    match lowercase || uppercase {
+        true => decode::h160(input),
+        false => parse_checksummed(input, chain_id),
+    }
+}
+
+/// Orders two tokens the way Uniswap style factories do, token0 is the lower address.
+pub fn sort_tokens(token_a: &str, token_b: &str) -> Result<(Address, Address)> {
+    let token_a = decode::h160(token_a)?;
+    let token_b = decode::h160(token_b)?;
+
+    match token_a.cmp(&token_b) {
+        Ordering::Less => Ok((token_a, token_b)),
+        Ordering::Greater => Ok((token_b, token_a)),
+        Ordering::Equal => Err(Error::data(format!("identical token addresses: {token_a}"))),
     }
 }
 
@@ -57,4 +165,90 @@ mod tests {
 
         Ok(())
     }
+
+    #[pg_test]
+    fn h160_checksum() -> Result<()> {
+        let checksum = Spi::get_one::<String>(
+            "SELECT H160.to_checksum('5aaeb6053f3e94c9b9a09f33669435e7ef1beaed');",
+        )?;
+
+        assert_eq!(
+            checksum,
+            Some(String::from("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"))
+        );
+
+        let rsk = Spi::get_one::<String>(
+            "SELECT H160.to_checksum('0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed', 30);",
+        )?;
+
+        assert_eq!(
+            rsk,
+            Some(String::from("0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD"))
+        );
+
+        let valid = Spi::get_one::<bool>(
+            "SELECT H160.is_checksum_valid('0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed');",
+        )?;
+
+        assert_eq!(valid, Some(true));
+
+        let lowercase = Spi::get_one::<bool>(
+            "SELECT H160.is_checksum_valid('0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed');",
+        )?;
+
+        assert_eq!(lowercase, Some(false));
+
+        let strict = Spi::get_one::<String>(
+            "SELECT H160.parse_strict('0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed');",
+        )?;
+
+        assert_eq!(
+            strict,
+            Some(String::from("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"))
+        );
+
+        let typo = Spi::get_one::<String>(
+            "SELECT H160.try_parse_strict('0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD');",
+        )?;
+
+        assert_eq!(typo, None);
+
+        Ok(())
+    }
+
+    #[pg_test(
+        error = "invalid input syntax for checksummed h160: \"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD\""
+    )]
+    fn h160_parse_strict_wrong_checksum() {
+        Spi::run("SELECT H160.parse_strict('0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD');")
+            .unwrap();
+    }
+
+    #[pg_test]
+    fn h160_create_address() -> Result<()> {
+        let created = Spi::get_one::<String>(
+            "SELECT H160.create_address('0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0', 1);",
+        )?;
+
+        assert_eq!(
+            created,
+            Some(String::from("343c43a37d37dff08ae8c4a11544c718abb4fcf8"))
+        );
+
+        // USDC / WETH pair of the Uniswap V2 factory
+        let pair = Spi::get_one::<String>(
+            "SELECT H160.create2_address(
+                '0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f',
+                H256.keccak256(decode('a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2', 'hex')),
+                '0x96e8ac4277198ff8b6f785478aa9a39f403cb768dd02cbee326c3e7da348845f'
+            );",
+        )?;
+
+        assert_eq!(
+            pair,
+            Some(String::from("b4e16d0168e52d35cacd2c6185b44281ec28c9dc"))
+        );
+
+        Ok(())
+    }
 }
@@ -1,31 +1,65 @@
 use pgrx::prelude::*;
 
+use alloy::primitives::B256;
+
+use crate::decode::{self, Error, Result};
+
 #[pg_schema]
 #[allow(non_snake_case)]
 mod H256 {
     use alloy::core::hex;
-    use alloy::core::primitives::{keccak256, B256};
+    use alloy::core::primitives::keccak256;
 
     use pgrx::prelude::*;
 
+    use super::slice_h256;
+    use crate::decode::{self, OrReport};
+
     #[pg_extern(name = "parse", immutable, parallel_safe)]
     fn parse_h256(string: &str) -> String {
-        hex::encode(string.parse::<B256>().unwrap())
+        hex::encode(decode::h256(string).or_report())
+    }
+
+    #[pg_extern(name = "try_parse", immutable, parallel_safe)]
+    fn try_parse_h256(string: &str) -> Option<String> {
+        decode::h256(string).ok().map(hex::encode)
     }
 
     #[pg_extern(immutable, parallel_safe)]
     fn parse_slice(string: &str, start: i64, end: i64) -> String {
-        hex::encode(
-            string[start as usize..end as usize]
-                .parse::<B256>()
-                .unwrap(),
-        )
+        hex::encode(slice_h256(string, start, end).or_report())
+    }
+
+    #[pg_extern(immutable, parallel_safe)]
+    fn try_parse_slice(string: &str, start: i64, end: i64) -> Option<String> {
+        slice_h256(string, start, end).ok().map(hex::encode)
     }
 
     #[pg_extern(name = "keccak256", immutable, parallel_safe)]
     fn to_keccak256(value: &str) -> String {
         hex::encode(keccak256(value.as_bytes()))
     }
+
+    #[pg_extern(name = "keccak256", immutable, parallel_safe)]
+    fn to_keccak256_bytea(value: &[u8]) -> String {
+        hex::encode(keccak256(value))
+    }
+}
+
+/// Parses the hex characters between `start` and `end` of `string`.
+fn slice_h256(string: &str, start: i64, end: i64) -> Result<B256> {
+    let slice = usize::try_from(start)
+        .ok()
+        .zip(usize::try_from(end).ok())
+        .and_then(|(start, end)| string.get(start..end))
+        .ok_or_else(|| {
+            Error::data(format!(
+                "slice {start}..{end} out of bounds for input of length {}",
+                string.len()
+            ))
+        })?;
+
+    decode::h256(slice)
 }
 
 #[cfg(any(test, feature = "pg_test"))]
@@ -89,4 +123,18 @@ mod tests {
 
         Ok(())
     }
+
+    #[pg_test]
+    fn h256_try_parse_slice() -> Result<()> {
+        let decoded = Spi::get_one::<&str>("SELECT H256.try_parse_slice('0020', 64, 128);")?;
+
+        assert_eq!(decoded, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "slice 64..128 out of bounds for input of length 4")]
+    fn h256_parse_slice_out_of_bounds() {
+        Spi::run("SELECT H256.parse_slice('0020', 64, 128);").unwrap();
+    }
 }
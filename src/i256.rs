@@ -0,0 +1,80 @@
+use pgrx::prelude::*;
+
+#[pg_schema]
+#[allow(non_snake_case)]
+mod I256 {
+    use pgrx::prelude::*;
+
+    use crate::types;
+
+    #[pg_extern(name = "to_hex", immutable, parallel_safe)]
+    fn to_hex_i256(value: types::I256) -> String {
+        format!("{:#x}", value.0)
+    }
+
+    #[pg_extern(name = "checked_add", immutable, parallel_safe)]
+    fn checked_add_i256(left: types::I256, right: types::I256) -> Option<types::I256> {
+        left.0.checked_add(right.0).map(types::I256)
+    }
+
+    #[pg_extern(name = "checked_sub", immutable, parallel_safe)]
+    fn checked_sub_i256(left: types::I256, right: types::I256) -> Option<types::I256> {
+        left.0.checked_sub(right.0).map(types::I256)
+    }
+
+    #[pg_extern(name = "checked_mul", immutable, parallel_safe)]
+    fn checked_mul_i256(left: types::I256, right: types::I256) -> Option<types::I256> {
+        left.0.checked_mul(right.0).map(types::I256)
+    }
+
+    #[pg_extern(name = "checked_div", immutable, parallel_safe)]
+    fn checked_div_i256(left: types::I256, right: types::I256) -> Option<types::I256> {
+        left.0.checked_div(right.0).map(types::I256)
+    }
+
+    #[pg_extern(name = "wrapping_add", immutable, parallel_safe)]
+    fn wrapping_add_i256(left: types::I256, right: types::I256) -> types::I256 {
+        types::I256(left.0.wrapping_add(right.0))
+    }
+
+    #[pg_extern(name = "wrapping_sub", immutable, parallel_safe)]
+    fn wrapping_sub_i256(left: types::I256, right: types::I256) -> types::I256 {
+        types::I256(left.0.wrapping_sub(right.0))
+    }
+
+    #[pg_extern(name = "wrapping_mul", immutable, parallel_safe)]
+    fn wrapping_mul_i256(left: types::I256, right: types::I256) -> types::I256 {
+        types::I256(left.0.wrapping_mul(right.0))
+    }
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn i256_checked_wrapping() -> Result<()> {
+        let min = "'-57896044618658097711785492504343953926634992332820282019728792003956564819968'::i256";
+
+        let checked =
+            Spi::get_one::<String>(&format!("SELECT I256.checked_sub({min}, '1'::i256)::text;"))?;
+
+        assert_eq!(checked, None);
+
+        let wrapped = Spi::get_one::<String>(&format!(
+            "SELECT I256.wrapping_sub({min}, '1'::i256)::text;"
+        ))?;
+
+        assert_eq!(
+            wrapped,
+            Some(String::from(
+                "57896044618658097711785492504343953926634992332820282019728792003956564819967"
+            ))
+        );
+
+        Ok(())
+    }
+}
@@ -4,15 +4,26 @@ mod erc20;
 mod erc721;
 mod h160;
 mod h256;
+mod i256;
 mod u256;
 
+mod decode;
+mod liquidity_amounts;
+mod tick_math;
+mod types;
+
+mod abi;
+
 mod cowswap;
+mod dex;
 mod sushiswap;
 mod uniswap;
+mod uniswap_v4;
 mod velodrome;
 
 mod base58;
 mod ed25519;
+mod solana;
 mod spl;
 
 #[cfg(test)]
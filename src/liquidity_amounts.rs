@@ -0,0 +1,122 @@
+//! Port of `LiquidityAmounts.sol` from Uniswap V3 periphery together with the `FullMath.mulDiv` it
+//! relies on, amounts are rounded down exactly like the contract does.
+
+use alloy::primitives::{ruint::UintTryTo, uint, U256, U512};
+
+use crate::decode::{Error, Result};
+
+const Q96: U256 = uint!(0x1000000000000000000000000_U256);
+
+/// `a * b / denominator` with a 512-bit intermediate, same as `FullMath.mulDiv`.
+fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    if denominator.is_zero() {
+        return Err(Error::data("division by zero"));
+    }
+
+    let result = a.widening_mul::<256, 4, 512, 8>(b) / U512::from(denominator);
+    UintTryTo::<U256>::uint_try_to(&result).map_err(|_| Error::data("mulDiv overflow"))
+}
+
+fn to_u128(liquidity: U256) -> Result<u128> {
+    u128::try_from(liquidity).map_err(|_| Error::data(format!("liquidity overflow: {liquidity}")))
+}
+
+fn sorted(sqrt_ratio_a: U256, sqrt_ratio_b: U256) -> (U256, U256) {
+    match sqrt_ratio_a > sqrt_ratio_b {
+        true => (sqrt_ratio_b, sqrt_ratio_a),
+        false => (sqrt_ratio_a, sqrt_ratio_b),
+    }
+}
+
+pub fn get_liquidity_for_amount0(
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    amount0: U256,
+) -> Result<u128> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = sorted(sqrt_ratio_a, sqrt_ratio_b);
+    let intermediate = mul_div(sqrt_ratio_a, sqrt_ratio_b, Q96)?;
+    to_u128(mul_div(amount0, intermediate, sqrt_ratio_b - sqrt_ratio_a)?)
+}
+
+pub fn get_liquidity_for_amount1(
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    amount1: U256,
+) -> Result<u128> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = sorted(sqrt_ratio_a, sqrt_ratio_b);
+    to_u128(mul_div(amount1, Q96, sqrt_ratio_b - sqrt_ratio_a)?)
+}
+
+/// Maximum liquidity `amount0` and `amount1` can mint in the range at the current price.
+pub fn get_liquidity_for_amounts(
+    sqrt_ratio: U256,
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    amount0: U256,
+    amount1: U256,
+) -> Result<u128> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = sorted(sqrt_ratio_a, sqrt_ratio_b);
+
+    if sqrt_ratio <= sqrt_ratio_a {
+        get_liquidity_for_amount0(sqrt_ratio_a, sqrt_ratio_b, amount0)
+    } else if sqrt_ratio < sqrt_ratio_b {
+        let liquidity0 = get_liquidity_for_amount0(sqrt_ratio, sqrt_ratio_b, amount0)?;
+        let liquidity1 = get_liquidity_for_amount1(sqrt_ratio_a, sqrt_ratio, amount1)?;
+        Ok(liquidity0.min(liquidity1))
+    } else {
+        get_liquidity_for_amount1(sqrt_ratio_a, sqrt_ratio_b, amount1)
+    }
+}
+
+pub fn get_amount0_for_liquidity(
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    liquidity: u128,
+) -> Result<U256> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = sorted(sqrt_ratio_a, sqrt_ratio_b);
+    let amount = mul_div(
+        U256::from(liquidity) << 96,
+        sqrt_ratio_b - sqrt_ratio_a,
+        sqrt_ratio_b,
+    )?;
+
+    amount
+        .checked_div(sqrt_ratio_a)
+        .ok_or_else(|| Error::data("division by zero"))
+}
+
+pub fn get_amount1_for_liquidity(
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    liquidity: u128,
+) -> Result<U256> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = sorted(sqrt_ratio_a, sqrt_ratio_b);
+    mul_div(U256::from(liquidity), sqrt_ratio_b - sqrt_ratio_a, Q96)
+}
+
+/// Token0 and token1 value of `liquidity` in the range at the current price.
+pub fn get_amounts_for_liquidity(
+    sqrt_ratio: U256,
+    sqrt_ratio_a: U256,
+    sqrt_ratio_b: U256,
+    liquidity: u128,
+) -> Result<(U256, U256)> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = sorted(sqrt_ratio_a, sqrt_ratio_b);
+
+    if sqrt_ratio <= sqrt_ratio_a {
+        Ok((
+            get_amount0_for_liquidity(sqrt_ratio_a, sqrt_ratio_b, liquidity)?,
+            U256::ZERO,
+        ))
+    } else if sqrt_ratio < sqrt_ratio_b {
+        Ok((
+            get_amount0_for_liquidity(sqrt_ratio, sqrt_ratio_b, liquidity)?,
+            get_amount1_for_liquidity(sqrt_ratio_a, sqrt_ratio, liquidity)?,
+        ))
+    } else {
+        Ok((
+            U256::ZERO,
+            get_amount1_for_liquidity(sqrt_ratio_a, sqrt_ratio_b, liquidity)?,
+        ))
+    }
+}
@@ -0,0 +1,246 @@
+use pgrx::prelude::*;
+
+use solana_sdk::pubkey::{Pubkey, PubkeyError, MAX_SEEDS, MAX_SEED_LEN};
+
+use crate::decode::{self, Error, Result, With};
+use crate::types;
+
+#[pg_schema]
+#[allow(non_snake_case)]
+mod Solana {
+    use pgrx::prelude::*;
+
+    use super::{create_program_address, create_with_seed, find_program_address, into_row};
+    use crate::decode::OrReport;
+    use crate::types;
+
+    extension_sql!(
+        r#"
+CREATE TYPE Solana.program_address AS (
+    address pubkey,
+    bump integer
+);
+"#,
+        name = "solana_program_address",
+        requires = [Pubkey],
+    );
+
+    #[pg_extern(
+        name = "find_program_address",
+        immutable,
+        parallel_safe,
+        requires = ["solana_program_address"]
+    )]
+    fn solana_find_program_address(
+        seeds: Array<&[u8]>,
+        program_id: types::Pubkey,
+    ) -> pgrx::composite_type!('static, "Solana.program_address") {
+        into_row(find_program_address(&seeds, &program_id.0).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_find_program_address",
+        immutable,
+        parallel_safe,
+        requires = ["solana_program_address"]
+    )]
+    fn solana_try_find_program_address(
+        seeds: Array<&[u8]>,
+        program_id: types::Pubkey,
+    ) -> Option<pgrx::composite_type!('static, "Solana.program_address")> {
+        find_program_address(&seeds, &program_id.0)
+            .ok()
+            .map(into_row)
+    }
+
+    #[pg_extern(name = "create_program_address", immutable, parallel_safe)]
+    fn solana_create_program_address(
+        seeds: Array<&[u8]>,
+        bump: i32,
+        program_id: types::Pubkey,
+    ) -> types::Pubkey {
+        types::Pubkey(create_program_address(&seeds, bump, &program_id.0).or_report())
+    }
+
+    #[pg_extern(name = "try_create_program_address", immutable, parallel_safe)]
+    fn solana_try_create_program_address(
+        seeds: Array<&[u8]>,
+        bump: i32,
+        program_id: types::Pubkey,
+    ) -> Option<types::Pubkey> {
+        create_program_address(&seeds, bump, &program_id.0)
+            .ok()
+            .map(types::Pubkey)
+    }
+
+    #[pg_extern(name = "create_with_seed", immutable, parallel_safe)]
+    fn solana_create_with_seed(
+        base: types::Pubkey,
+        seed: &str,
+        owner: types::Pubkey,
+    ) -> types::Pubkey {
+        types::Pubkey(create_with_seed(&base.0, seed, &owner.0).or_report())
+    }
+
+    #[pg_extern(name = "try_create_with_seed", immutable, parallel_safe)]
+    fn solana_try_create_with_seed(
+        base: types::Pubkey,
+        seed: &str,
+        owner: types::Pubkey,
+    ) -> Option<types::Pubkey> {
+        create_with_seed(&base.0, seed, &owner.0)
+            .ok()
+            .map(types::Pubkey)
+    }
+}
+
+fn into_row((address, bump): (Pubkey, u8)) -> decode::Row {
+    decode::row("Solana.program_address")
+        .with("address", types::Pubkey(address))
+        .with("bump", bump as i32)
+}
+
+/// Collects the seeds of a program address, the bump seed is appended later so it counts too.
+fn collect_seeds<'a>(seeds: &'a Array<'_, &[u8]>) -> Result<Vec<&'a [u8]>> {
+    if seeds.len() >= MAX_SEEDS {
+        return Err(Error::data(format!(
+            "too many seeds: expected at most {}, got {}",
+            MAX_SEEDS - 1,
+            seeds.len()
+        )));
+    }
+
+    seeds
+        .iter()
+        .enumerate()
+        .map(|(index, seed)| {
+            let seed = seed.ok_or_else(|| Error::data("seeds must not contain NULL"))?;
+            if seed.len() > MAX_SEED_LEN {
+                return Err(Error::data(format!(
+                    "seed {index} too long: expected at most {MAX_SEED_LEN} bytes, got {}",
+                    seed.len()
+                )));
+            }
+            Ok(seed)
+        })
+        .collect()
+}
+
+/// Canonical program address, the first bump seed counting down from 255 that is off the curve.
+fn find_program_address(seeds: &Array<&[u8]>, program_id: &Pubkey) -> Result<(Pubkey, u8)> {
+    Pubkey::try_find_program_address(&collect_seeds(seeds)?, program_id)
+        .ok_or_else(|| Error::data("no bump seed results in a valid program address"))
+}
+
+fn create_program_address(seeds: &Array<&[u8]>, bump: i32, program_id: &Pubkey) -> Result<Pubkey> {
+    let bump = u8::try_from(bump).map_err(|_| Error::data(format!("bump out of range: {bump}")))?;
+
+    let bump = [bump];
+    let mut seeds = collect_seeds(seeds)?;
+    seeds.push(&bump);
+
+    Pubkey::create_program_address(&seeds, program_id).map_err(|error| match error {
+        PubkeyError::InvalidSeeds => Error::data(format!(
+            "invalid seeds: bump {} results in an address on the ed25519 curve",
+            bump[0]
+        )),
+        error => Error::data(error.to_string()),
+    })
+}
+
+/// Address of an account created with `create_account_with_seed`, `seed` is at most 32 bytes.
+fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Result<Pubkey> {
+    Pubkey::create_with_seed(base, seed, owner).map_err(|error| match error {
+        PubkeyError::MaxSeedLengthExceeded => Error::data(format!(
+            "seed too long: expected at most {MAX_SEED_LEN} bytes, got {}",
+            seed.len()
+        )),
+        PubkeyError::IllegalOwner => Error::data(format!(
+            "illegal owner: {owner} is a program derived address"
+        )),
+        error => Error::data(error.to_string()),
+    })
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn solana_test_find_program_address() -> Result<()> {
+        // Associated token account of the SPL.token_account test
+        let (address, bump) = Spi::get_two::<String, i32>(
+            "SELECT address::text, bump FROM Solana.find_program_address(ARRAY['D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb'::pubkey::bytea, 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey::bytea, 'CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu'::pubkey::bytea], 'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL');",
+        )?;
+
+        assert_eq!(
+            address,
+            Some(String::from("45TCoQ8FSp4USRsGkuDKVQmZs878wgPAmhKYJBGWnEYd"))
+        );
+        assert_eq!(bump, Some(254));
+
+        let metadata = Spi::get_one::<String>(
+            "SELECT (Solana.find_program_address(ARRAY['metadata'::bytea, 'metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s'::pubkey::bytea, 'CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu'::pubkey::bytea], 'metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s')).address::text;",
+        )?;
+
+        assert_eq!(
+            metadata,
+            Some(String::from("2qXDCcyZfvu6mVSZEMQigPLK8699qaQPQW53hCePGNhF"))
+        );
+
+        let long = Spi::get_one::<String>(
+            "SELECT (Solana.try_find_program_address(ARRAY[decode(repeat('00', 33), 'hex')], 'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL')).address::text;",
+        )?;
+
+        assert_eq!(long, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn solana_test_create_program_address() -> Result<()> {
+        let address = Spi::get_one::<String>(
+            "SELECT Solana.create_program_address(ARRAY['D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb'::pubkey::bytea, 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey::bytea, 'CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu'::pubkey::bytea], 254, 'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL')::text;",
+        )?;
+
+        assert_eq!(
+            address,
+            Some(String::from("45TCoQ8FSp4USRsGkuDKVQmZs878wgPAmhKYJBGWnEYd"))
+        );
+
+        let on_curve = Spi::get_one::<String>(
+            "SELECT Solana.try_create_program_address(ARRAY['D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb'::pubkey::bytea, 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey::bytea, 'CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu'::pubkey::bytea], 255, 'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL')::text;",
+        )?;
+
+        assert_eq!(on_curve, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "bump out of range: 256")]
+    fn solana_test_create_program_address_bump() {
+        Spi::run("SELECT Solana.create_program_address(ARRAY['metadata'::bytea], 256, 'metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');").unwrap();
+    }
+
+    #[pg_test]
+    fn solana_test_create_with_seed() -> Result<()> {
+        let address = Spi::get_one::<String>(
+            "SELECT Solana.create_with_seed('D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb', 'stake:0', 'Stake11111111111111111111111111111111111111')::text;",
+        )?;
+
+        assert_eq!(
+            address,
+            Some(String::from("HG4cYCxiZYmCZzXapeGanCDZGhfL8vB1bUiBrpfsXMPX"))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test(error = "seed too long: expected at most 32 bytes, got 33")]
+    fn solana_test_create_with_seed_too_long() {
+        Spi::run("SELECT Solana.create_with_seed('D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb', repeat('a', 33), 'Stake11111111111111111111111111111111111111');").unwrap();
+    }
+}
@@ -1,62 +1,877 @@
 use pgrx::prelude::*;
 
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decode::{self, Error, Result, With};
+
 #[pg_schema]
 #[allow(non_snake_case)]
 mod SPL {
     use pgrx::prelude::*;
     use solana_sdk::{pubkey, pubkey::Pubkey};
-    use std::str::FromStr;
+
+    use super::{
+        decode_instruction, decode_transfer, decode_transfer_fee, instruction_row,
+        transfer_account, transfer_accounts, transfer_fee_row, transfer_row, transfer_value,
+    };
+    use crate::decode::{self, OrReport};
+    use crate::types;
+
+    extension_sql!(
+        r#"
+CREATE TYPE SPL.transfer AS (
+    amount numeric,
+    decimals integer,
+    fee numeric
+);
+
+CREATE TYPE SPL.transfer_fee_instruction AS (
+    instruction_type text,
+    amount numeric,
+    decimals integer,
+    fee numeric,
+    transfer_fee_basis_points integer,
+    maximum_fee numeric,
+    transfer_fee_config_authority text,
+    withdraw_withheld_authority text,
+    num_token_accounts integer
+);
+
+CREATE TYPE SPL.instruction AS (
+    instruction_type text,
+    amount numeric,
+    decimals integer,
+    source text,
+    destination text,
+    mint text,
+    authority text
+);
+"#,
+        name = "spl_types",
+    );
 
     #[pg_extern(name = "transfer_source", immutable, parallel_safe)]
     fn spl_transfer_source(accounts: Array<&str>) -> String {
-        accounts
-            .get(0)
-            .expect("array to be accessible")
-            .expect("0 to be source")
-            .into()
+        transfer_account(&accounts, 0).or_report().into()
+    }
+
+    #[pg_extern(name = "try_transfer_source", immutable, parallel_safe)]
+    fn spl_try_transfer_source(accounts: Array<&str>) -> Option<String> {
+        transfer_account(&accounts, 0).ok().map(String::from)
     }
 
     #[pg_extern(name = "transfer_destination", immutable, parallel_safe)]
     fn spl_transfer_destination(accounts: Array<&str>) -> String {
-        accounts
-            .get(1)
-            .expect("array to be accessible")
-            .expect("1 to be destination")
-            .into()
+        transfer_account(&accounts, 1).or_report().into()
+    }
+
+    #[pg_extern(name = "try_transfer_destination", immutable, parallel_safe)]
+    fn spl_try_transfer_destination(accounts: Array<&str>) -> Option<String> {
+        transfer_account(&accounts, 1).ok().map(String::from)
+    }
+
+    #[pg_extern(name = "transfer_source", immutable, parallel_safe)]
+    fn spl_transfer_source_data(data: &str, accounts: Array<&str>) -> String {
+        let (source, _) = transfer_accounts(&decode::base58(data).or_report()).or_report();
+        transfer_account(&accounts, source).or_report().into()
+    }
+
+    #[pg_extern(name = "transfer_source", immutable, parallel_safe)]
+    fn spl_transfer_source_data_bytea(data: &[u8], accounts: Array<&str>) -> String {
+        let (source, _) = transfer_accounts(data).or_report();
+        transfer_account(&accounts, source).or_report().into()
+    }
+
+    #[pg_extern(name = "try_transfer_source", immutable, parallel_safe)]
+    fn spl_try_transfer_source_data(data: &str, accounts: Array<&str>) -> Option<String> {
+        let (source, _) = transfer_accounts(&decode::base58(data).ok()?).ok()?;
+        transfer_account(&accounts, source).ok().map(String::from)
+    }
+
+    #[pg_extern(name = "try_transfer_source", immutable, parallel_safe)]
+    fn spl_try_transfer_source_data_bytea(data: &[u8], accounts: Array<&str>) -> Option<String> {
+        let (source, _) = transfer_accounts(data).ok()?;
+        transfer_account(&accounts, source).ok().map(String::from)
+    }
+
+    #[pg_extern(name = "transfer_destination", immutable, parallel_safe)]
+    fn spl_transfer_destination_data(data: &str, accounts: Array<&str>) -> String {
+        let (_, destination) = transfer_accounts(&decode::base58(data).or_report()).or_report();
+        transfer_account(&accounts, destination).or_report().into()
+    }
+
+    #[pg_extern(name = "transfer_destination", immutable, parallel_safe)]
+    fn spl_transfer_destination_data_bytea(data: &[u8], accounts: Array<&str>) -> String {
+        let (_, destination) = transfer_accounts(data).or_report();
+        transfer_account(&accounts, destination).or_report().into()
+    }
+
+    #[pg_extern(name = "try_transfer_destination", immutable, parallel_safe)]
+    fn spl_try_transfer_destination_data(data: &str, accounts: Array<&str>) -> Option<String> {
+        let (_, destination) = transfer_accounts(&decode::base58(data).ok()?).ok()?;
+        transfer_account(&accounts, destination)
+            .ok()
+            .map(String::from)
+    }
+
+    #[pg_extern(name = "try_transfer_destination", immutable, parallel_safe)]
+    fn spl_try_transfer_destination_data_bytea(
+        data: &[u8],
+        accounts: Array<&str>,
+    ) -> Option<String> {
+        let (_, destination) = transfer_accounts(data).ok()?;
+        transfer_account(&accounts, destination)
+            .ok()
+            .map(String::from)
     }
 
     #[pg_extern(name = "transfer_value", immutable, parallel_safe)]
     fn spl_transfer_value(data: &str) -> pgrx::AnyNumeric {
-        let slice = bs58::decode(data).into_vec().expect("can base58 decode");
-        assert_eq!(slice[0], 3, "instruction discriminator should be 3");
-        pgrx::AnyNumeric::try_from(
-            u64::from_le_bytes(slice[1..9].try_into().expect("slice to be 8 bytes"))
-                .to_string()
-                .as_str(),
+        decode::numeric(transfer_value(&decode::base58(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "transfer_value", immutable, parallel_safe)]
+    fn spl_transfer_value_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        decode::numeric(transfer_value(data).or_report())
+    }
+
+    #[pg_extern(name = "try_transfer_value", immutable, parallel_safe)]
+    fn spl_try_transfer_value(data: &str) -> Option<pgrx::AnyNumeric> {
+        transfer_value(&decode::base58(data).ok()?)
+            .ok()
+            .map(decode::numeric)
+    }
+
+    #[pg_extern(name = "try_transfer_value", immutable, parallel_safe)]
+    fn spl_try_transfer_value_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        transfer_value(data).ok().map(decode::numeric)
+    }
+
+    #[pg_extern(name = "decode_transfer", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_decode_transfer(data: &str) -> pgrx::composite_type!('static, "SPL.transfer") {
+        transfer_row(decode_transfer(&decode::base58(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "decode_transfer", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_decode_transfer_bytea(data: &[u8]) -> pgrx::composite_type!('static, "SPL.transfer") {
+        transfer_row(decode_transfer(data).or_report())
+    }
+
+    #[pg_extern(name = "try_decode_transfer", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_try_decode_transfer(
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "SPL.transfer")> {
+        decode_transfer(&decode::base58(data).ok()?)
+            .ok()
+            .map(transfer_row)
+    }
+
+    #[pg_extern(name = "try_decode_transfer", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_try_decode_transfer_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "SPL.transfer")> {
+        decode_transfer(data).ok().map(transfer_row)
+    }
+
+    #[pg_extern(name = "decode_transfer_fee", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_decode_transfer_fee(
+        data: &str,
+    ) -> pgrx::composite_type!('static, "SPL.transfer_fee_instruction") {
+        transfer_fee_row(decode_transfer_fee(&decode::base58(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "decode_transfer_fee", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_decode_transfer_fee_bytea(
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "SPL.transfer_fee_instruction") {
+        transfer_fee_row(decode_transfer_fee(data).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_transfer_fee",
+        immutable,
+        parallel_safe,
+        requires = ["spl_types"]
+    )]
+    fn spl_try_decode_transfer_fee(
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "SPL.transfer_fee_instruction")> {
+        decode_transfer_fee(&decode::base58(data).ok()?)
+            .ok()
+            .map(transfer_fee_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_transfer_fee",
+        immutable,
+        parallel_safe,
+        requires = ["spl_types"]
+    )]
+    fn spl_try_decode_transfer_fee_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "SPL.transfer_fee_instruction")> {
+        decode_transfer_fee(data).ok().map(transfer_fee_row)
+    }
+
+    #[pg_extern(name = "decode_instruction", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_decode_instruction(
+        data: &str,
+        accounts: Array<&str>,
+    ) -> pgrx::composite_type!('static, "SPL.instruction") {
+        instruction_row(
+            decode_instruction(&decode::base58(data).or_report(), &accounts).or_report(),
         )
-        .expect("can convert u64 to AnyNumeric")
+    }
+
+    #[pg_extern(name = "decode_instruction", immutable, parallel_safe, requires = ["spl_types"])]
+    fn spl_decode_instruction_bytea(
+        data: &[u8],
+        accounts: Array<&str>,
+    ) -> pgrx::composite_type!('static, "SPL.instruction") {
+        instruction_row(decode_instruction(data, &accounts).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_instruction",
+        immutable,
+        parallel_safe,
+        requires = ["spl_types"]
+    )]
+    fn spl_try_decode_instruction(
+        data: &str,
+        accounts: Array<&str>,
+    ) -> Option<pgrx::composite_type!('static, "SPL.instruction")> {
+        decode_instruction(&decode::base58(data).ok()?, &accounts)
+            .ok()
+            .map(instruction_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_instruction",
+        immutable,
+        parallel_safe,
+        requires = ["spl_types"]
+    )]
+    fn spl_try_decode_instruction_bytea(
+        data: &[u8],
+        accounts: Array<&str>,
+    ) -> Option<pgrx::composite_type!('static, "SPL.instruction")> {
+        decode_instruction(data, &accounts)
+            .ok()
+            .map(instruction_row)
     }
 
     const PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
     const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 
     #[pg_extern(name = "token_account", immutable, parallel_safe)]
-    fn spl_token_account(mint: String, address: String) -> Result<String, anyhow::Error> {
-        let mint = Pubkey::from_str(mint.as_str())?;
-        let address = Pubkey::from_str(address.as_str())?;
+    fn spl_token_account(mint: String, address: String) -> String {
+        let mint = decode::pubkey(mint.as_str()).or_report();
+        let address = decode::pubkey(address.as_str()).or_report();
+
+        token_account(&mint, &address, &TOKEN_PROGRAM_ID).to_string()
+    }
+
+    #[pg_extern(name = "token_account", immutable, parallel_safe)]
+    fn spl_token_account_pubkey(mint: types::Pubkey, address: types::Pubkey) -> types::Pubkey {
+        types::Pubkey(token_account(&mint.0, &address.0, &TOKEN_PROGRAM_ID))
+    }
+
+    #[pg_extern(name = "token_account", immutable, parallel_safe)]
+    fn spl_token_account_program(mint: &str, address: &str, token_program: &str) -> String {
+        let mint = decode::pubkey(mint).or_report();
+        let address = decode::pubkey(address).or_report();
+        let token_program = decode::pubkey(token_program).or_report();
+
+        token_account(&mint, &address, &token_program).to_string()
+    }
 
+    #[pg_extern(name = "token_account", immutable, parallel_safe)]
+    fn spl_token_account_program_pubkey(
+        mint: types::Pubkey,
+        address: types::Pubkey,
+        token_program: types::Pubkey,
+    ) -> types::Pubkey {
+        types::Pubkey(token_account(&mint.0, &address.0, &token_program.0))
+    }
+
+    /// Associated token account of `address`, the token program is the legacy one or Token-2022.
+    fn token_account(mint: &Pubkey, address: &Pubkey, token_program: &Pubkey) -> Pubkey {
         let seeds = [
             &address.to_bytes()[..],
-            &TOKEN_PROGRAM_ID.to_bytes()[..],
+            &token_program.to_bytes()[..],
             &mint.to_bytes()[..],
         ];
 
-        Ok(Pubkey::find_program_address(&seeds, &PROGRAM_ID)
-            .0
-            .to_string())
+        Pubkey::find_program_address(&seeds, &PROGRAM_ID).0
     }
 }
 
+fn transfer_account<'a>(accounts: &'a Array<'_, &str>, index: usize) -> Result<&'a str> {
+    accounts
+        .get(index)
+        .flatten()
+        .ok_or_else(|| Error::data(format!("missing account {index}")))
+}
+
+const TRANSFER: u8 = 3;
+const TRANSFER_CHECKED: u8 = 12;
+/// Token-2022 only, the extension instruction follows in the second byte.
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+
+/// Amounts moved by a Transfer, TransferChecked or Token-2022 TransferCheckedWithFee instruction.
+pub struct Transfer {
+    pub amount: u64,
+    pub decimals: Option<u8>,
+    pub fee: Option<u64>,
+}
+
+/// Instructions of the Token-2022 transfer fee extension.
+pub enum TransferFeeInstruction {
+    InitializeTransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    TransferCheckedWithFee {
+        amount: u64,
+        decimals: u8,
+        fee: u64,
+    },
+    WithdrawWithheldTokensFromMint,
+    WithdrawWithheldTokensFromAccounts {
+        num_token_accounts: u8,
+    },
+    HarvestWithheldTokensToMint,
+    SetTransferFee {
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+}
+
+impl TransferFeeInstruction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransferFeeInstruction::InitializeTransferFeeConfig { .. } => {
+                "InitializeTransferFeeConfig"
+            }
+            TransferFeeInstruction::TransferCheckedWithFee { .. } => "TransferCheckedWithFee",
+            TransferFeeInstruction::WithdrawWithheldTokensFromMint => {
+                "WithdrawWithheldTokensFromMint"
+            }
+            TransferFeeInstruction::WithdrawWithheldTokensFromAccounts { .. } => {
+                "WithdrawWithheldTokensFromAccounts"
+            }
+            TransferFeeInstruction::HarvestWithheldTokensToMint => "HarvestWithheldTokensToMint",
+            TransferFeeInstruction::SetTransferFee { .. } => "SetTransferFee",
+        }
+    }
+}
+
+/// Any token program instruction flattened into the columns of `SPL.instruction`, `source` is the
+/// token account the instruction acts on and `destination` the account receiving tokens, lamports
+/// or the delegation. Fields that do not apply to the instruction are None.
+#[derive(Default)]
+pub struct Instruction {
+    pub instruction_type: &'static str,
+    pub amount: Option<u64>,
+    pub decimals: Option<u8>,
+    pub source: Option<String>,
+    pub destination: Option<String>,
+    pub mint: Option<String>,
+    pub authority: Option<String>,
+}
+
+fn discriminator(data: &[u8]) -> Result<u8> {
+    data.first()
+        .copied()
+        .ok_or_else(|| Error::data("instruction data is empty"))
+}
+
+/// Decodes any of the transfer instructions, the discriminator is checked so other instructions
+/// are rejected.
+fn decode_transfer(data: &[u8]) -> Result<Transfer> {
+    match discriminator(data)? {
+        TRANSFER => Ok(Transfer {
+            amount: read_u64(data, 1)?,
+            decimals: None,
+            fee: None,
+        }),
+        TRANSFER_CHECKED => Ok(Transfer {
+            amount: read_u64(data, 1)?,
+            decimals: Some(read_u8(data, 9)?),
+            fee: None,
+        }),
+        TRANSFER_FEE_EXTENSION => match decode_transfer_fee(data)? {
+            TransferFeeInstruction::TransferCheckedWithFee {
+                amount,
+                decimals,
+                fee,
+            } => Ok(Transfer {
+                amount,
+                decimals: Some(decimals),
+                fee: Some(fee),
+            }),
+            instruction => Err(Error::data(format!(
+                "invalid transfer fee instruction: expected TransferCheckedWithFee, got {}",
+                instruction.name()
+            ))),
+        },
+        discriminator => Err(Error::data(format!(
+            "invalid instruction discriminator: expected 3, 12 or 26, got {discriminator}"
+        ))),
+    }
+}
+
+fn transfer_value(data: &[u8]) -> Result<u64> {
+    Ok(decode_transfer(data)?.amount)
+}
+
+/// Indexes of the source and destination account of a transfer instruction, TransferChecked and
+/// TransferCheckedWithFee pass the mint between them.
+fn transfer_accounts(data: &[u8]) -> Result<(usize, usize)> {
+    decode_transfer(data)?;
+
+    Ok(match discriminator(data)? {
+        TRANSFER => (0, 1),
+        _ => (0, 2),
+    })
+}
+
+fn decode_transfer_fee(data: &[u8]) -> Result<TransferFeeInstruction> {
+    match discriminator(data)? {
+        TRANSFER_FEE_EXTENSION => {}
+        discriminator => {
+            return Err(Error::data(format!(
+                "invalid instruction discriminator: expected 26, got {discriminator}"
+            )))
+        }
+    }
+
+    match read_u8(data, 1)? {
+        0 => {
+            let (transfer_fee_config_authority, offset) = read_pubkey_option(data, 2)?;
+            let (withdraw_withheld_authority, offset) = read_pubkey_option(data, offset)?;
+            Ok(TransferFeeInstruction::InitializeTransferFeeConfig {
+                transfer_fee_config_authority,
+                withdraw_withheld_authority,
+                transfer_fee_basis_points: read_u16(data, offset)?,
+                maximum_fee: read_u64(data, offset + 2)?,
+            })
+        }
+        1 => Ok(TransferFeeInstruction::TransferCheckedWithFee {
+            amount: read_u64(data, 2)?,
+            decimals: read_u8(data, 10)?,
+            fee: read_u64(data, 11)?,
+        }),
+        2 => Ok(TransferFeeInstruction::WithdrawWithheldTokensFromMint),
+        3 => Ok(TransferFeeInstruction::WithdrawWithheldTokensFromAccounts {
+            num_token_accounts: read_u8(data, 2)?,
+        }),
+        4 => Ok(TransferFeeInstruction::HarvestWithheldTokensToMint),
+        5 => Ok(TransferFeeInstruction::SetTransferFee {
+            transfer_fee_basis_points: read_u16(data, 2)?,
+            maximum_fee: read_u64(data, 4)?,
+        }),
+        instruction => Err(Error::data(format!(
+            "invalid transfer fee instruction: {instruction}"
+        ))),
+    }
+}
+
+/// Decodes an instruction of the token program or Token-2022, `accounts` are the accounts of the
+/// instruction in order.
+fn decode_instruction(data: &[u8], accounts: &Array<&str>) -> Result<Instruction> {
+    let account =
+        |index| transfer_account(accounts, index).map(|account| Some(String::from(account)));
+    let key = |offset| read_pubkey(data, offset).map(|key| Some(key.to_string()));
+
+    Ok(match discriminator(data)? {
+        0 => Instruction {
+            instruction_type: "InitializeMint",
+            decimals: Some(read_u8(data, 1)?),
+            mint: account(0)?,
+            authority: key(2)?,
+            ..Default::default()
+        },
+        1 => Instruction {
+            instruction_type: "InitializeAccount",
+            source: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        2 => Instruction {
+            instruction_type: "InitializeMultisig",
+            source: account(0)?,
+            ..Default::default()
+        },
+        TRANSFER => Instruction {
+            instruction_type: "Transfer",
+            amount: Some(read_u64(data, 1)?),
+            source: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        4 => Instruction {
+            instruction_type: "Approve",
+            amount: Some(read_u64(data, 1)?),
+            source: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        5 => Instruction {
+            instruction_type: "Revoke",
+            source: account(0)?,
+            authority: account(1)?,
+            ..Default::default()
+        },
+        6 => Instruction {
+            instruction_type: "SetAuthority",
+            source: account(0)?,
+            authority: account(1)?,
+            ..Default::default()
+        },
+        7 => Instruction {
+            instruction_type: "MintTo",
+            amount: Some(read_u64(data, 1)?),
+            mint: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        8 => Instruction {
+            instruction_type: "Burn",
+            amount: Some(read_u64(data, 1)?),
+            source: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        9 => Instruction {
+            instruction_type: "CloseAccount",
+            source: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        10 => Instruction {
+            instruction_type: "FreezeAccount",
+            source: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        11 => Instruction {
+            instruction_type: "ThawAccount",
+            source: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        TRANSFER_CHECKED => Instruction {
+            instruction_type: "TransferChecked",
+            amount: Some(read_u64(data, 1)?),
+            decimals: Some(read_u8(data, 9)?),
+            source: account(0)?,
+            mint: account(1)?,
+            destination: account(2)?,
+            authority: account(3)?,
+        },
+        13 => Instruction {
+            instruction_type: "ApproveChecked",
+            amount: Some(read_u64(data, 1)?),
+            decimals: Some(read_u8(data, 9)?),
+            source: account(0)?,
+            mint: account(1)?,
+            destination: account(2)?,
+            authority: account(3)?,
+        },
+        14 => Instruction {
+            instruction_type: "MintToChecked",
+            amount: Some(read_u64(data, 1)?),
+            decimals: Some(read_u8(data, 9)?),
+            mint: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        15 => Instruction {
+            instruction_type: "BurnChecked",
+            amount: Some(read_u64(data, 1)?),
+            decimals: Some(read_u8(data, 9)?),
+            source: account(0)?,
+            mint: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        16 => Instruction {
+            instruction_type: "InitializeAccount2",
+            source: account(0)?,
+            mint: account(1)?,
+            authority: key(1)?,
+            ..Default::default()
+        },
+        17 => Instruction {
+            instruction_type: "SyncNative",
+            source: account(0)?,
+            ..Default::default()
+        },
+        18 => Instruction {
+            instruction_type: "InitializeAccount3",
+            source: account(0)?,
+            mint: account(1)?,
+            authority: key(1)?,
+            ..Default::default()
+        },
+        19 => Instruction {
+            instruction_type: "InitializeMultisig2",
+            source: account(0)?,
+            ..Default::default()
+        },
+        20 => Instruction {
+            instruction_type: "InitializeMint2",
+            decimals: Some(read_u8(data, 1)?),
+            mint: account(0)?,
+            authority: key(2)?,
+            ..Default::default()
+        },
+        21 => Instruction {
+            instruction_type: "GetAccountDataSize",
+            mint: account(0)?,
+            ..Default::default()
+        },
+        22 => Instruction {
+            instruction_type: "InitializeImmutableOwner",
+            source: account(0)?,
+            ..Default::default()
+        },
+        23 => Instruction {
+            instruction_type: "AmountToUiAmount",
+            amount: Some(read_u64(data, 1)?),
+            mint: account(0)?,
+            ..Default::default()
+        },
+        24 => Instruction {
+            instruction_type: "UiAmountToAmount",
+            mint: account(0)?,
+            ..Default::default()
+        },
+        25 => Instruction {
+            instruction_type: "InitializeMintCloseAuthority",
+            mint: account(0)?,
+            authority: read_pubkey_option(data, 1)?.0.map(|key| key.to_string()),
+            ..Default::default()
+        },
+        TRANSFER_FEE_EXTENSION => {
+            let instruction = decode_transfer_fee(data)?;
+            let instruction_type = instruction.name();
+
+            match instruction {
+                TransferFeeInstruction::InitializeTransferFeeConfig {
+                    transfer_fee_config_authority,
+                    ..
+                } => Instruction {
+                    instruction_type,
+                    mint: account(0)?,
+                    authority: transfer_fee_config_authority.map(|key| key.to_string()),
+                    ..Default::default()
+                },
+                TransferFeeInstruction::TransferCheckedWithFee {
+                    amount, decimals, ..
+                } => Instruction {
+                    instruction_type,
+                    amount: Some(amount),
+                    decimals: Some(decimals),
+                    source: account(0)?,
+                    mint: account(1)?,
+                    destination: account(2)?,
+                    authority: account(3)?,
+                },
+                TransferFeeInstruction::WithdrawWithheldTokensFromMint
+                | TransferFeeInstruction::WithdrawWithheldTokensFromAccounts { .. } => {
+                    Instruction {
+                        instruction_type,
+                        mint: account(0)?,
+                        destination: account(1)?,
+                        authority: account(2)?,
+                        ..Default::default()
+                    }
+                }
+                TransferFeeInstruction::HarvestWithheldTokensToMint => Instruction {
+                    instruction_type,
+                    mint: account(0)?,
+                    ..Default::default()
+                },
+                TransferFeeInstruction::SetTransferFee { .. } => Instruction {
+                    instruction_type,
+                    mint: account(0)?,
+                    authority: account(1)?,
+                    ..Default::default()
+                },
+            }
+        }
+        29 => Instruction {
+            instruction_type: "Reallocate",
+            source: account(0)?,
+            authority: account(3)?,
+            ..Default::default()
+        },
+        31 => Instruction {
+            instruction_type: "CreateNativeMint",
+            mint: account(1)?,
+            ..Default::default()
+        },
+        32 => Instruction {
+            instruction_type: "InitializeNonTransferableMint",
+            mint: account(0)?,
+            ..Default::default()
+        },
+        35 => Instruction {
+            instruction_type: "InitializePermanentDelegate",
+            mint: account(0)?,
+            authority: key(1)?,
+            ..Default::default()
+        },
+        38 => Instruction {
+            instruction_type: "WithdrawExcessLamports",
+            source: account(0)?,
+            destination: account(1)?,
+            authority: account(2)?,
+            ..Default::default()
+        },
+        // Extensions with their own sub-instructions, only the extension is named for now
+        discriminator => Instruction {
+            instruction_type: match discriminator {
+                27 => "ConfidentialTransferExtension",
+                28 => "DefaultAccountStateExtension",
+                30 => "MemoTransferExtension",
+                33 => "InterestBearingMintExtension",
+                34 => "CpiGuardExtension",
+                36 => "TransferHookExtension",
+                37 => "ConfidentialTransferFeeExtension",
+                39 => "MetadataPointerExtension",
+                40 => "GroupPointerExtension",
+                41 => "GroupMemberPointerExtension",
+                42 => "ConfidentialMintBurnExtension",
+                43 => "ScaledUiAmountExtension",
+                44 => "PausableExtension",
+                _ => {
+                    return Err(Error::data(format!(
+                        "invalid instruction discriminator: {discriminator}"
+                    )))
+                }
+            },
+            ..Default::default()
+        },
+    })
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8> {
+    Ok(decode::slice(data, offset, offset + 1)?[0])
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    let bytes = decode::slice(data, offset, offset + 2)?;
+    Ok(u16::from_le_bytes(
+        bytes.try_into().expect("slice to be 2 bytes"),
+    ))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let bytes = decode::slice(data, offset, offset + 8)?;
+    Ok(u64::from_le_bytes(
+        bytes.try_into().expect("slice to be 8 bytes"),
+    ))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
+    let bytes = decode::slice(data, offset, offset + 32)?;
+    Ok(Pubkey::new_from_array(
+        bytes.try_into().expect("slice to be 32 bytes"),
+    ))
+}
+
+/// Optional pubkey packed as a one byte tag followed by the key only when the tag is 1, returns
+/// the offset after it as well.
+fn read_pubkey_option(data: &[u8], offset: usize) -> Result<(Option<Pubkey>, usize)> {
+    match read_u8(data, offset)? {
+        0 => Ok((None, offset + 1)),
+        1 => Ok((Some(read_pubkey(data, offset + 1)?), offset + 33)),
+        tag => Err(Error::data(format!("invalid option tag: {tag}"))),
+    }
+}
+
+fn transfer_row(transfer: Transfer) -> decode::Row {
+    decode::row("SPL.transfer")
+        .with("amount", decode::numeric(transfer.amount))
+        .with("decimals", transfer.decimals.map(i32::from))
+        .with("fee", transfer.fee.map(decode::numeric))
+}
+
+fn transfer_fee_row(instruction: TransferFeeInstruction) -> decode::Row {
+    let row =
+        decode::row("SPL.transfer_fee_instruction").with("instruction_type", instruction.name());
+
+    match instruction {
+        TransferFeeInstruction::InitializeTransferFeeConfig {
+            transfer_fee_config_authority,
+            withdraw_withheld_authority,
+            transfer_fee_basis_points,
+            maximum_fee,
+        } => row
+            .with(
+                "transfer_fee_config_authority",
+                transfer_fee_config_authority.map(|key| key.to_string()),
+            )
+            .with(
+                "withdraw_withheld_authority",
+                withdraw_withheld_authority.map(|key| key.to_string()),
+            )
+            .with(
+                "transfer_fee_basis_points",
+                i32::from(transfer_fee_basis_points),
+            )
+            .with("maximum_fee", decode::numeric(maximum_fee)),
+        TransferFeeInstruction::TransferCheckedWithFee {
+            amount,
+            decimals,
+            fee,
+        } => row
+            .with("amount", decode::numeric(amount))
+            .with("decimals", i32::from(decimals))
+            .with("fee", decode::numeric(fee)),
+        TransferFeeInstruction::WithdrawWithheldTokensFromAccounts { num_token_accounts } => {
+            row.with("num_token_accounts", i32::from(num_token_accounts))
+        }
+        TransferFeeInstruction::SetTransferFee {
+            transfer_fee_basis_points,
+            maximum_fee,
+        } => row
+            .with(
+                "transfer_fee_basis_points",
+                i32::from(transfer_fee_basis_points),
+            )
+            .with("maximum_fee", decode::numeric(maximum_fee)),
+        TransferFeeInstruction::WithdrawWithheldTokensFromMint
+        | TransferFeeInstruction::HarvestWithheldTokensToMint => row,
+    }
+}
+
+fn instruction_row(instruction: Instruction) -> decode::Row {
+    decode::row("SPL.instruction")
+        .with("instruction_type", instruction.instruction_type)
+        .with("amount", instruction.amount.map(decode::numeric))
+        .with("decimals", instruction.decimals.map(i32::from))
+        .with("source", instruction.source)
+        .with("destination", instruction.destination)
+        .with("mint", instruction.mint)
+        .with("authority", instruction.authority)
+}
+
 #[cfg(any(test, feature = "pg_test"))]
 #[pg_schema]
 mod tests {
@@ -79,6 +894,77 @@ mod tests {
 
         assert_eq!(decoded, Some(pgrx::AnyNumeric::from_str("10321")?));
 
+        // TransferChecked and Token-2022 TransferCheckedWithFee
+        let (checked, with_fee) = Spi::get_two::<pgrx::AnyNumeric, pgrx::AnyNumeric>(
+            "SELECT SPL.transfer_value('h8s1kVQgUEiLV'), SPL.transfer_value('5m8HoFX19eHCP1BvYDgPyLz3WF');",
+        )?;
+
+        assert_eq!(checked, Some(pgrx::AnyNumeric::from_str("10321")?));
+        assert_eq!(with_fee, Some(pgrx::AnyNumeric::from_str("10321")?));
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn spl_test_decode_transfer() -> Result<()> {
+        let (amount, decimals, fee) = Spi::get_three::<pgrx::AnyNumeric, i32, pgrx::AnyNumeric>(
+            "SELECT amount, decimals, fee FROM SPL.decode_transfer('5m8HoFX19eHCP1BvYDgPyLz3WF');",
+        )?;
+
+        assert_eq!(amount, Some(pgrx::AnyNumeric::from_str("10321")?));
+        assert_eq!(decimals, Some(6));
+        assert_eq!(fee, Some(pgrx::AnyNumeric::from_str("103")?));
+
+        let (decimals, fee) = Spi::get_two::<i32, pgrx::AnyNumeric>(
+            "SELECT decimals, fee FROM SPL.decode_transfer(decode('035128000000000000', 'hex'));",
+        )?;
+
+        assert_eq!(decimals, None);
+        assert_eq!(fee, None);
+
+        let mint_to = Spi::get_one::<pgrx::AnyNumeric>(
+            "SELECT amount FROM SPL.try_decode_transfer('6QJxoHjttZEf');",
+        )?;
+
+        assert_eq!(mint_to, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn spl_test_decode_transfer_fee() -> Result<()> {
+        let (instruction_type, basis_points, maximum_fee) =
+            Spi::get_three::<String, i32, pgrx::AnyNumeric>(
+                "SELECT instruction_type, transfer_fee_basis_points, maximum_fee FROM SPL.decode_transfer_fee(decode('1a053200404b4c0000000000', 'hex'));",
+            )?;
+
+        assert_eq!(instruction_type, Some(String::from("SetTransferFee")));
+        assert_eq!(basis_points, Some(50));
+        assert_eq!(maximum_fee, Some(pgrx::AnyNumeric::from_str("5000000")?));
+
+        let (config_authority, withdraw_authority, basis_points) =
+            Spi::get_three::<String, String, i32>(
+                "SELECT transfer_fee_config_authority, withdraw_withheld_authority, transfer_fee_basis_points FROM SPL.decode_transfer_fee('3qgxpgRk2We3zAw8SRApbtjRvY3MHeBkxVLQCawa9Jrhqpo4ot7kCyW4huSpTZq');",
+            )?;
+
+        assert_eq!(
+            config_authority,
+            Some(String::from("D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb"))
+        );
+        assert_eq!(withdraw_authority, None);
+        assert_eq!(basis_points, Some(50));
+
+        let accounts =
+            Spi::get_one::<i32>("SELECT num_token_accounts FROM SPL.decode_transfer_fee('9jkV');")?;
+
+        assert_eq!(accounts, Some(2));
+
+        let transfer = Spi::get_one::<String>(
+            "SELECT instruction_type FROM SPL.try_decode_transfer_fee('3T2t139PouH1');",
+        )?;
+
+        assert_eq!(transfer, None);
+
         Ok(())
     }
 
@@ -98,6 +984,134 @@ mod tests {
             Some(String::from("45TCoQ8FSp4USRsGkuDKVQmZs878wgPAmhKYJBGWnEYd"))
         );
 
+        let native = Spi::get_one::<String>(
+            "SELECT SPL.token_account('CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu'::pubkey, 'D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb'::pubkey)::text;",
+        )?;
+
+        assert_eq!(native, decoded);
+
+        let (legacy, token_2022) = Spi::get_two::<String, String>(
+            "SELECT SPL.token_account('CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu', 'D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb', 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'), SPL.token_account('CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu'::pubkey, 'D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb'::pubkey, 'TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb'::pubkey)::text;",
+        )?;
+
+        assert_eq!(legacy, decoded);
+        assert_eq!(
+            token_2022,
+            Some(String::from("Bjx2Srz1VCdjJhDELidHoXnEUjykBy6KEvSKwfqPTSLm"))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn spl_test_decode_instruction() -> Result<()> {
+        let accounts = "{Source1111111111111111111111111111111111111,CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu,Destination11111111111111111111111111111111,D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb}";
+
+        let (instruction_type, amount, decimals) =
+            Spi::get_three_with_args::<String, pgrx::AnyNumeric, i32>(
+                "SELECT instruction_type, amount, decimals FROM SPL.decode_instruction('h8s1kVQgUEiLV', $1::text[]);",
+                &vec![DatumWithOid::from(accounts)],
+            )?;
+
+        assert_eq!(instruction_type, Some(String::from("TransferChecked")));
+        assert_eq!(amount, Some(pgrx::AnyNumeric::from_str("10321")?));
+        assert_eq!(decimals, Some(6));
+
+        let (source, destination, mint) = Spi::get_three_with_args::<String, String, String>(
+            "SELECT source, destination, mint FROM SPL.decode_instruction('h8s1kVQgUEiLV', $1::text[]);",
+            &vec![DatumWithOid::from(accounts)],
+        )?;
+
+        assert_eq!(
+            source,
+            Some(String::from("Source1111111111111111111111111111111111111"))
+        );
+        assert_eq!(
+            destination,
+            Some(String::from("Destination11111111111111111111111111111111"))
+        );
+        assert_eq!(
+            mint,
+            Some(String::from("CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu"))
+        );
+
+        // Burn lists the account, the mint and the owner
+        let (instruction_type, mint, authority) = Spi::get_three::<String, String, String>(
+            "SELECT instruction_type, mint, authority FROM SPL.decode_instruction(decode('085128000000000000', 'hex'), '{Source1111111111111111111111111111111111111,CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu,D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb}');",
+        )?;
+
+        assert_eq!(instruction_type, Some(String::from("Burn")));
+        assert_eq!(
+            mint,
+            Some(String::from("CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu"))
+        );
+        assert_eq!(
+            authority,
+            Some(String::from("D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb"))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn spl_test_decode_instruction_initialize_mint() -> Result<()> {
+        // The mint authority is part of the instruction data, not of the accounts
+        let (decimals, mint, authority) = Spi::get_three::<i32, String, String>(
+            "SELECT decimals, mint, authority FROM SPL.decode_instruction(decode('0006b32ca67b3d2fb87ed7e28e3671a1795571b24f02b073a9ef23ec8bd1aa6930c200', 'hex'), '{CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu}');",
+        )?;
+
+        assert_eq!(decimals, Some(6));
+        assert_eq!(
+            mint,
+            Some(String::from("CY2E69dSG9vBsMoaXDvYmMDSMEP4SZtRY1rqVQ9tkNDu"))
+        );
+        assert_eq!(
+            authority,
+            Some(String::from("D4RU5YKeMuHc25rrgmbggwr95DaogDe8d8hFRD2CNQXb"))
+        );
+
+        let missing = Spi::get_one::<String>(
+            "SELECT instruction_type FROM SPL.try_decode_instruction('3T2t139PouH1', '{Source1111111111111111111111111111111111111}');",
+        )?;
+
+        assert_eq!(missing, None);
+
         Ok(())
     }
+
+    #[pg_test(error = "invalid instruction discriminator: 45")]
+    fn spl_test_decode_instruction_unknown() {
+        Spi::run("SELECT SPL.decode_instruction(decode('2d', 'hex'), '{}');").unwrap();
+    }
+
+    #[pg_test]
+    fn spl_test_transfer_accounts() -> Result<()> {
+        let (source, destination) = Spi::get_two::<String, String>(
+            "SELECT SPL.transfer_source('3T2t139PouH1', '{source,destination,authority}'), SPL.transfer_destination('3T2t139PouH1', '{source,destination,authority}');",
+        )?;
+
+        assert_eq!(source, Some(String::from("source")));
+        assert_eq!(destination, Some(String::from("destination")));
+
+        // TransferChecked and TransferCheckedWithFee list the mint before the destination
+        let (checked, with_fee) = Spi::get_two::<String, String>(
+            "SELECT SPL.transfer_destination('h8s1kVQgUEiLV', '{source,mint,destination,authority}'), SPL.transfer_destination('5m8HoFX19eHCP1BvYDgPyLz3WF', '{source,mint,destination,authority}');",
+        )?;
+
+        assert_eq!(checked, Some(String::from("destination")));
+        assert_eq!(with_fee, Some(String::from("destination")));
+
+        let mint_to = Spi::get_one::<String>(
+            "SELECT SPL.try_transfer_destination('6QJxoHjttZEf', '{mint,destination,authority}');",
+        )?;
+
+        assert_eq!(mint_to, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid instruction discriminator: expected 3, 12 or 26, got 7")]
+    fn spl_test_transfer_value_wrong_instruction() {
+        Spi::run("SELECT SPL.transfer_value('6QJxoHjttZEf');").unwrap();
+    }
 }
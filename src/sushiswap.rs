@@ -1,9 +1,11 @@
 use pgrx::prelude::*;
 
+use alloy::primitives::{keccak256, Address};
 use bigdecimal::BigDecimal;
-use num::{bigint::Sign, BigInt};
+use num::{bigint::Sign, BigInt, Zero};
 
-use anyhow::Result;
+use crate::decode::{self, Error, Result, With};
+use crate::h160::sort_tokens;
 
 pub enum SwapAction {
     SELL = -1,
@@ -26,85 +28,327 @@ pub struct Sync {
 mod Sushiswap {
     use pgrx::prelude::*;
 
-    use std::error::Error;
-    use std::str::FromStr;
-
     use alloy::core::hex;
 
-    use super::decode_swap;
-    use super::decode_sync;
-    use super::sync_price;
+    use super::{decode_swap, decode_sync, pair_for, swap_row, sync_price, sync_row};
+    use crate::decode::{self, OrReport, Topics};
+    use crate::h160::sort_tokens;
+
+    extension_sql!(
+        r#"
+CREATE TYPE Sushiswap.swap AS (
+    action integer,
+    base_amount numeric,
+    quote_amount numeric
+);
+
+CREATE TYPE Sushiswap.sync AS (
+    base_reserve numeric,
+    quote_reserve numeric
+);
+"#,
+        name = "sushiswap_types",
+    );
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["sushiswap_types"])]
+    fn sushi_decode_swap(data: &str) -> pgrx::composite_type!('static, "Sushiswap.swap") {
+        swap_row(decode_swap(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["sushiswap_types"])]
+    fn sushi_decode_swap_bytea(data: &[u8]) -> pgrx::composite_type!('static, "Sushiswap.swap") {
+        swap_row(decode_swap(data).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["sushiswap_types"]
+    )]
+    fn sushi_try_decode_swap(
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Sushiswap.swap")> {
+        decode_swap(&decode::hex(data).ok()?).ok().map(swap_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["sushiswap_types"]
+    )]
+    fn sushi_try_decode_swap_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Sushiswap.swap")> {
+        decode_swap(data).ok().map(swap_row)
+    }
+
+    #[pg_extern(name = "decode_sync", immutable, parallel_safe, requires = ["sushiswap_types"])]
+    fn sushi_decode_sync(data: &str) -> pgrx::composite_type!('static, "Sushiswap.sync") {
+        sync_row(decode_sync(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "decode_sync", immutable, parallel_safe, requires = ["sushiswap_types"])]
+    fn sushi_decode_sync_bytea(data: &[u8]) -> pgrx::composite_type!('static, "Sushiswap.sync") {
+        sync_row(decode_sync(data).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_sync",
+        immutable,
+        parallel_safe,
+        requires = ["sushiswap_types"]
+    )]
+    fn sushi_try_decode_sync(
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Sushiswap.sync")> {
+        decode_sync(&decode::hex(data).ok()?).ok().map(sync_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_sync",
+        immutable,
+        parallel_safe,
+        requires = ["sushiswap_types"]
+    )]
+    fn sushi_try_decode_sync_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Sushiswap.sync")> {
+        decode_sync(data).ok().map(sync_row)
+    }
+
+    #[pg_extern(name = "swap_sender", immutable, parallel_safe)]
+    fn sushi_swap_sender(topics: Array<&str>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "swap_sender_raw", immutable, parallel_safe)]
+    fn sushi_swap_sender_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_sender", immutable, parallel_safe)]
+    fn sushi_try_swap_sender(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_swap_sender_raw", immutable, parallel_safe)]
+    fn sushi_try_swap_sender_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "swap_recipient", immutable, parallel_safe)]
+    fn sushi_swap_recipient(topics: Array<&str>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "swap_recipient_raw", immutable, parallel_safe)]
+    fn sushi_swap_recipient_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_recipient", immutable, parallel_safe)]
+    fn sushi_try_swap_recipient(topics: Array<&str>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_swap_recipient_raw", immutable, parallel_safe)]
+    fn sushi_try_swap_recipient_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
 
     #[pg_extern(name = "swap_type", immutable, parallel_safe)]
     fn sushi_swap_type(data: &str) -> i32 {
-        decode_swap(&hex::decode(data).unwrap()).unwrap().action as i32
+        decode_swap(&decode::hex(data).or_report())
+            .or_report()
+            .action as i32
+    }
+
+    #[pg_extern(name = "swap_type", immutable, parallel_safe)]
+    fn sushi_swap_type_bytea(data: &[u8]) -> i32 {
+        decode_swap(data).or_report().action as i32
+    }
+
+    #[pg_extern(name = "try_swap_type", immutable, parallel_safe)]
+    fn sushi_try_swap_type(data: &str) -> Option<i32> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(swap.action as i32)
+    }
+
+    #[pg_extern(name = "try_swap_type", immutable, parallel_safe)]
+    fn sushi_try_swap_type_bytea(data: &[u8]) -> Option<i32> {
+        let swap = decode_swap(data).ok()?;
+        Some(swap.action as i32)
+    }
+
+    #[pg_extern(name = "swap_base_amount", immutable, parallel_safe)]
+    fn sushi_swap_base_amount(data: &str) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(swap.base_amount)
     }
 
     #[pg_extern(name = "swap_base_amount", immutable, parallel_safe)]
-    fn sushi_swap_base_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_swap(&hex::decode(data).unwrap())
-                .unwrap()
-                .base_amount
-                .to_string()
-                .as_str(),
-        )?)
+    fn sushi_swap_base_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(swap.base_amount)
+    }
+
+    #[pg_extern(name = "try_swap_base_amount", immutable, parallel_safe)]
+    fn sushi_try_swap_base_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(swap.base_amount))
+    }
+
+    #[pg_extern(name = "try_swap_base_amount", immutable, parallel_safe)]
+    fn sushi_try_swap_base_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(swap.base_amount))
+    }
+
+    #[pg_extern(name = "swap_quote_amount", immutable, parallel_safe)]
+    fn sushi_swap_quote_amount(data: &str) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(swap.quote_amount)
     }
 
     #[pg_extern(name = "swap_quote_amount", immutable, parallel_safe)]
-    fn sushi_swap_quote_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_swap(&hex::decode(data).unwrap())
-                .unwrap()
-                .quote_amount
-                .to_string()
-                .as_str(),
-        )?)
+    fn sushi_swap_quote_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(swap.quote_amount)
+    }
+
+    #[pg_extern(name = "try_swap_quote_amount", immutable, parallel_safe)]
+    fn sushi_try_swap_quote_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(swap.quote_amount))
+    }
+
+    #[pg_extern(name = "try_swap_quote_amount", immutable, parallel_safe)]
+    fn sushi_try_swap_quote_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(swap.quote_amount))
     }
 
     #[pg_extern(name = "sync_base_reserve", immutable, parallel_safe)]
-    fn sushi_sync_base_reserve(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_sync(&hex::decode(data).unwrap())
-                .unwrap()
-                .base_reserve
-                .to_string()
-                .as_str(),
-        )?)
+    fn sushi_sync_base_reserve(data: &str) -> pgrx::AnyNumeric {
+        let sync = decode_sync(&decode::hex(data).or_report()).or_report();
+        decode::numeric(sync.base_reserve)
+    }
+
+    #[pg_extern(name = "sync_base_reserve", immutable, parallel_safe)]
+    fn sushi_sync_base_reserve_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let sync = decode_sync(data).or_report();
+        decode::numeric(sync.base_reserve)
+    }
+
+    #[pg_extern(name = "try_sync_base_reserve", immutable, parallel_safe)]
+    fn sushi_try_sync_base_reserve(data: &str) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(sync.base_reserve))
+    }
+
+    #[pg_extern(name = "try_sync_base_reserve", immutable, parallel_safe)]
+    fn sushi_try_sync_base_reserve_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(data).ok()?;
+        Some(decode::numeric(sync.base_reserve))
     }
 
     #[pg_extern(name = "sync_quote_reserve", immutable, parallel_safe)]
-    fn sushi_sync_quote_reserve(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_sync(&hex::decode(data).unwrap())
-                .unwrap()
-                .quote_reserve
-                .to_string()
-                .as_str(),
-        )?)
+    fn sushi_sync_quote_reserve(data: &str) -> pgrx::AnyNumeric {
+        let sync = decode_sync(&decode::hex(data).or_report()).or_report();
+        decode::numeric(sync.quote_reserve)
+    }
+
+    #[pg_extern(name = "sync_quote_reserve", immutable, parallel_safe)]
+    fn sushi_sync_quote_reserve_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let sync = decode_sync(data).or_report();
+        decode::numeric(sync.quote_reserve)
+    }
+
+    #[pg_extern(name = "try_sync_quote_reserve", immutable, parallel_safe)]
+    fn sushi_try_sync_quote_reserve(data: &str) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(sync.quote_reserve))
+    }
+
+    #[pg_extern(name = "try_sync_quote_reserve", immutable, parallel_safe)]
+    fn sushi_try_sync_quote_reserve_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(data).ok()?;
+        Some(decode::numeric(sync.quote_reserve))
+    }
+
+    #[pg_extern(name = "sync_price", immutable, parallel_safe)]
+    fn sushi_sync_price(data: &str, base_decimals: i64, quote_decimals: i64) -> pgrx::AnyNumeric {
+        let price = sync_price(
+            &decode::hex(data).or_report(),
+            base_decimals,
+            quote_decimals,
+        )
+        .or_report();
+
+        decode::numeric(price)
     }
 
     #[pg_extern(name = "sync_price", immutable, parallel_safe)]
-    fn sushi_sync_price(
+    fn sushi_sync_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> pgrx::AnyNumeric {
+        let price = sync_price(data, base_decimals, quote_decimals).or_report();
+
+        decode::numeric(price)
+    }
+
+    #[pg_extern(name = "try_sync_price", immutable, parallel_safe)]
+    fn sushi_try_sync_price(
         data: &str,
         base_decimals: i64,
         quote_decimals: i64,
-    ) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            sync_price(&hex::decode(data).unwrap(), base_decimals, quote_decimals)
-                .to_string()
-                .as_str(),
-        )?)
+    ) -> Option<pgrx::AnyNumeric> {
+        let price = sync_price(&decode::hex(data).ok()?, base_decimals, quote_decimals).ok()?;
+        Some(decode::numeric(price))
+    }
+
+    #[pg_extern(name = "try_sync_price", immutable, parallel_safe)]
+    fn sushi_try_sync_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let price = sync_price(data, base_decimals, quote_decimals).ok()?;
+        Some(decode::numeric(price))
+    }
+
+    #[pg_extern(name = "sort_tokens", immutable, parallel_safe)]
+    fn sushi_sort_tokens(token_a: &str, token_b: &str) -> Vec<String> {
+        let (token0, token1) = sort_tokens(token_a, token_b).or_report();
+        vec![hex::encode(token0), hex::encode(token1)]
+    }
+
+    #[pg_extern(name = "pair_for", immutable, parallel_safe)]
+    fn sushi_pair_for(factory: &str, token_a: &str, token_b: &str, init_code_hash: &str) -> String {
+        hex::encode(pair_for(factory, token_a, token_b, init_code_hash).or_report())
     }
 }
 
-#[allow(dead_code)]
-fn decode_swap(data: &[u8]) -> Result<Swap> {
-    let amount_0_in = BigInt::from_bytes_be(Sign::Plus, &data[64..96]);
-    let amount_1_in = BigInt::from_bytes_be(Sign::Plus, &data[96..128]);
+/// CREATE2 address of a pair, the salt is keccak256 of the packed sorted token addresses.
+fn pair_for(factory: &str, token_a: &str, token_b: &str, init_code_hash: &str) -> Result<Address> {
+    let factory = decode::h160(factory)?;
+    let init_code_hash = decode::h256(init_code_hash)?;
+    let (token0, token1) = sort_tokens(token_a, token_b)?;
 
-    let amount_0_out = BigInt::from_bytes_be(Sign::Plus, &data[128..160]);
-    let amount_1_out = BigInt::from_bytes_be(Sign::Plus, &data[160..192]);
+    let salt = keccak256([token0.as_slice(), token1.as_slice()].concat());
+    Ok(factory.create2(salt, init_code_hash))
+}
+
+pub fn decode_swap(data: &[u8]) -> Result<Swap> {
+    let amount_0_in = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 64, 96)?);
+    let amount_1_in = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 96, 128)?);
+
+    let amount_0_out = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 128, 160)?);
+    let amount_1_out = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 160, 192)?);
 
     let action = match amount_0_in.gt(&BigInt::from(0u32)) {
         true => SwapAction::SELL,
@@ -121,10 +365,9 @@ fn decode_swap(data: &[u8]) -> Result<Swap> {
     })
 }
 
-#[allow(dead_code)]
 fn decode_sync(data: &[u8]) -> Result<Sync> {
-    let base_reserve = BigInt::from_bytes_be(Sign::Plus, &data[64..96]);
-    let quote_reserve = BigInt::from_bytes_be(Sign::Plus, &data[96..128]);
+    let base_reserve = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 64, 96)?);
+    let quote_reserve = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 96, 128)?);
 
     Ok(Sync {
         base_reserve,
@@ -132,14 +375,33 @@ fn decode_sync(data: &[u8]) -> Result<Sync> {
     })
 }
 
-fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> BigDecimal {
-    let base_reserve = BigInt::from_bytes_be(Sign::Plus, &bytes[64..96]);
-    let quote_reserve = BigInt::from_bytes_be(Sign::Plus, &bytes[96..128]);
+fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> Result<BigDecimal> {
+    let Sync {
+        base_reserve,
+        quote_reserve,
+    } = decode_sync(bytes)?;
+
+    if base_reserve.is_zero() {
+        return Err(Error::data("base reserve is zero"));
+    }
 
     let decimal_base_reserve = BigDecimal::new(base_reserve, base_decimals);
     let decimal_quote_reserve = BigDecimal::new(quote_reserve, quote_decimals);
 
-    return (decimal_quote_reserve / decimal_base_reserve).round(quote_decimals);
+    Ok((decimal_quote_reserve / decimal_base_reserve).round(quote_decimals))
+}
+
+fn swap_row(swap: Swap) -> decode::Row {
+    decode::row("Sushiswap.swap")
+        .with("action", swap.action as i32)
+        .with("base_amount", decode::numeric(swap.base_amount))
+        .with("quote_amount", decode::numeric(swap.quote_amount))
+}
+
+fn sync_row(sync: Sync) -> decode::Row {
+    decode::row("Sushiswap.sync")
+        .with("base_reserve", decode::numeric(sync.base_reserve))
+        .with("quote_reserve", decode::numeric(sync.quote_reserve))
 }
 
 #[cfg(any(test, feature = "pg_test"))]
@@ -229,4 +491,64 @@ mod tests {
 
         Ok(())
     }
+
+    #[pg_test]
+    fn sushi_test_decode_sync() -> Result<()> {
+        let data = "000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000030a017596c201728ecfb31300000000000000000000000000000000000000000000009a2946f7338c7c7108";
+
+        let (base_reserve, quote_reserve) =
+            Spi::get_two_with_args::<pgrx::AnyNumeric, pgrx::AnyNumeric>(
+                "SELECT base_reserve, quote_reserve FROM Sushiswap.decode_sync($1);",
+                &vec![DatumWithOid::from(data)],
+            )?;
+
+        assert_eq!(
+            base_reserve,
+            Some(pgrx::AnyNumeric::from_str("940551179158967834289091347")?)
+        );
+        assert_eq!(
+            quote_reserve,
+            Some(pgrx::AnyNumeric::from_str("2843772923755968098568")?)
+        );
+
+        let short = Spi::get_one::<pgrx::AnyNumeric>(
+            "SELECT base_reserve FROM Sushiswap.try_decode_sync('0020');",
+        )?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn sushi_test_pair_for() -> Result<()> {
+        let tokens = Spi::get_one::<Vec<String>>(
+            "SELECT Sushiswap.sort_tokens('0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2', '0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48');",
+        )?;
+
+        assert_eq!(
+            tokens,
+            Some(vec![
+                String::from("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+                String::from("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+            ])
+        );
+
+        // USDC / WETH on the Uniswap V2 factory, Sushiswap pairs are derived the same way
+        let pair = Spi::get_one::<String>(
+            "SELECT Sushiswap.pair_for(
+                '0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f',
+                '0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2',
+                '0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48',
+                '0x96e8ac4277198ff8b6f785478aa9a39f403cb768dd02cbee326c3e7da348845f'
+            );",
+        )?;
+
+        assert_eq!(
+            pair,
+            Some(String::from("b4e16d0168e52d35cacd2c6185b44281ec28c9dc"))
+        );
+
+        Ok(())
+    }
 }
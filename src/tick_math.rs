@@ -0,0 +1,119 @@
+//! Port of `TickMath.sol` from Uniswap V3 core, every step is done in the same integer widths as
+//! the contract so the results match on-chain values exactly.
+
+use alloy::primitives::{uint, I256, U256};
+
+use crate::decode::{Error, Result};
+
+pub const MIN_TICK: i32 = -887272;
+pub const MAX_TICK: i32 = -MIN_TICK;
+
+pub const MIN_SQRT_RATIO: U256 = uint!(4295128739_U256);
+pub const MAX_SQRT_RATIO: U256 = uint!(1461446703485210103287273052203988822378723970342_U256);
+
+/// Multipliers for each bit of the absolute tick, `2^128 / sqrt(1.0001)^(2^i)` in Q128.128.
+const RATIOS: [u128; 19] = [
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+    0x48a170391f7dc42444e8fa2,
+];
+
+/// `sqrt(1.0001^tick) * 2^96`, same as `TickMath.getSqrtRatioAtTick`.
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> Result<U256> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(Error::data(format!("tick out of range: {tick}")));
+    }
+
+    let abs_tick = tick.unsigned_abs();
+
+    let mut ratio = match abs_tick & 0x1 != 0 {
+        true => U256::from(0xfffcb933bd6fad37aa2d162d1a594001u128),
+        false => U256::from(1) << 128,
+    };
+
+    for (bit, multiplier) in RATIOS.iter().enumerate() {
+        if abs_tick & (0x2 << bit) != 0 {
+            ratio = (ratio * U256::from(*multiplier)) >> 128;
+        }
+    }
+
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Round up so that getTickAtSqrtRatio of the result is always consistent
+    let rounding = match (ratio & U256::from(u32::MAX)).is_zero() {
+        true => U256::ZERO,
+        false => U256::from(1),
+    };
+
+    Ok((ratio >> 32) + rounding)
+}
+
+/// Greatest tick whose sqrt ratio is less than or equal to `sqrt_price_x96`, same as
+/// `TickMath.getTickAtSqrtRatio`.
+pub fn get_tick_at_sqrt_ratio(sqrt_price_x96: U256) -> Result<i32> {
+    if sqrt_price_x96 < MIN_SQRT_RATIO || sqrt_price_x96 >= MAX_SQRT_RATIO {
+        return Err(Error::data(format!(
+            "sqrtPriceX96 out of range: {sqrt_price_x96}"
+        )));
+    }
+
+    let ratio: U256 = sqrt_price_x96 << 32;
+
+    let msb = ratio.bit_len() - 1;
+    let mut r = match msb >= 128 {
+        true => ratio >> (msb - 127),
+        false => ratio << (127 - msb),
+    };
+
+    let mut log_2 =
+        I256::from_raw(U256::from(msb)).wrapping_sub(I256::from_raw(uint!(128_U256))) << 64;
+
+    for shift in (50..=63).rev() {
+        r = (r * r) >> 127;
+        let f: U256 = r >> 128;
+        log_2 |= I256::from_raw(f << shift);
+        r >>= f.to::<usize>();
+    }
+
+    // log_sqrt10001 is a Q128.128 number
+    let log_sqrt10001 = log_2 * I256::from_raw(uint!(255738958999603826347141_U256));
+
+    let tick_low = tick_from_q128(
+        log_sqrt10001 - I256::from_raw(uint!(3402992956809132418596140100660247210_U256)),
+    );
+    let tick_high = tick_from_q128(
+        log_sqrt10001 + I256::from_raw(uint!(291339464771989622907027621153398088495_U256)),
+    );
+
+    if tick_low == tick_high {
+        return Ok(tick_low);
+    }
+
+    match get_sqrt_ratio_at_tick(tick_high)? <= sqrt_price_x96 {
+        true => Ok(tick_high),
+        false => Ok(tick_low),
+    }
+}
+
+/// Integer part of a signed Q128.128 number, truncated to int24 like the Solidity cast.
+fn tick_from_q128(value: I256) -> i32 {
+    value.asr(128).low_i32()
+}
@@ -0,0 +1,271 @@
+use pgrx::aggregate::*;
+use pgrx::prelude::*;
+use pgrx::{AnyNumeric, StringInfo};
+
+use alloy::primitives::{self, U512};
+
+use std::ffi::CStr;
+
+use super::u256::out_of_range;
+use super::{fixed_bytes_datum, FixedBytes, I256, U256};
+
+/// Running sum and count for `avg(u256)`, the sum is kept in 512 bits so it cannot overflow.
+#[derive(Copy, Clone, Debug, PostgresType)]
+#[bikeshed_postgres_type_manually_impl_from_into_datum]
+#[inoutfuncs]
+pub struct U256AvgState {
+    sum: U512,
+    count: u64,
+}
+
+impl FixedBytes<72> for U256AvgState {
+    fn from_bytes(bytes: [u8; 72]) -> Self {
+        U256AvgState {
+            sum: U512::from_be_slice(&bytes[..64]),
+            count: u64::from_be_bytes(bytes[64..].try_into().expect("slice to be 8 bytes")),
+        }
+    }
+
+    fn to_bytes(&self) -> [u8; 72] {
+        let mut bytes = [0u8; 72];
+        bytes[..64].copy_from_slice(&self.sum.to_be_bytes::<64>());
+        bytes[64..].copy_from_slice(&self.count.to_be_bytes());
+        bytes
+    }
+}
+
+fixed_bytes_datum!(U256AvgState, 72);
+
+impl InOutFuncs for U256AvgState {
+    fn input(input: &CStr) -> Self {
+        let text = input.to_string_lossy();
+
+        let parsed = text.split_once(',').and_then(|(sum, count)| {
+            Some(U256AvgState {
+                sum: sum.trim().parse().ok()?,
+                count: count.trim().parse().ok()?,
+            })
+        });
+
+        match parsed {
+            Some(state) => state,
+            None => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+                    format!("invalid input syntax for type u256avgstate: \"{text}\"")
+                );
+            }
+        }
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        buffer.push_str(&format!("{},{}", self.sum, self.count));
+    }
+}
+
+pub struct U256Sum;
+
+#[pg_aggregate]
+impl Aggregate for U256Sum {
+    const NAME: &'static str = "sum";
+    const PARALLEL: Option<ParallelOption> = Some(ParallelOption::Safe);
+
+    type State = U256;
+    type Args = U256;
+
+    #[pgrx(immutable, parallel_safe)]
+    fn state(current: Self::State, value: Self::Args, _fcinfo: pg_sys::FunctionCallInfo) -> U256 {
+        U256(
+            current
+                .0
+                .checked_add(value.0)
+                .unwrap_or_else(|| out_of_range("u256")),
+        )
+    }
+
+    #[pgrx(immutable, parallel_safe)]
+    fn combine(current: U256, other: U256, fcinfo: pg_sys::FunctionCallInfo) -> U256 {
+        Self::state(current, other, fcinfo)
+    }
+}
+
+pub struct U256Min;
+
+#[pg_aggregate]
+impl Aggregate for U256Min {
+    const NAME: &'static str = "min";
+    const PARALLEL: Option<ParallelOption> = Some(ParallelOption::Safe);
+    const SORT_OPERATOR: Option<&'static str> = Some("<");
+
+    type State = U256;
+    type Args = U256;
+
+    #[pgrx(immutable, parallel_safe)]
+    fn state(current: Self::State, value: Self::Args, _fcinfo: pg_sys::FunctionCallInfo) -> U256 {
+        current.min(value)
+    }
+
+    #[pgrx(immutable, parallel_safe)]
+    fn combine(current: U256, other: U256, _fcinfo: pg_sys::FunctionCallInfo) -> U256 {
+        current.min(other)
+    }
+}
+
+pub struct U256Max;
+
+#[pg_aggregate]
+impl Aggregate for U256Max {
+    const NAME: &'static str = "max";
+    const PARALLEL: Option<ParallelOption> = Some(ParallelOption::Safe);
+    const SORT_OPERATOR: Option<&'static str> = Some(">");
+
+    type State = U256;
+    type Args = U256;
+
+    #[pgrx(immutable, parallel_safe)]
+    fn state(current: Self::State, value: Self::Args, _fcinfo: pg_sys::FunctionCallInfo) -> U256 {
+        current.max(value)
+    }
+
+    #[pgrx(immutable, parallel_safe)]
+    fn combine(current: U256, other: U256, _fcinfo: pg_sys::FunctionCallInfo) -> U256 {
+        current.max(other)
+    }
+}
+
+pub struct U256Avg;
+
+#[pg_aggregate]
+impl Aggregate for U256Avg {
+    const NAME: &'static str = "avg";
+    const PARALLEL: Option<ParallelOption> = Some(ParallelOption::Safe);
+    const INITIAL_CONDITION: Option<&'static str> = Some("0,0");
+
+    type State = U256AvgState;
+    type Args = U256;
+    type Finalize = Option<AnyNumeric>;
+
+    #[pgrx(immutable, parallel_safe)]
+    fn state(
+        current: Self::State,
+        value: Self::Args,
+        _fcinfo: pg_sys::FunctionCallInfo,
+    ) -> U256AvgState {
+        U256AvgState {
+            sum: current.sum + U512::from(value.0),
+            count: current.count + 1,
+        }
+    }
+
+    #[pgrx(immutable, parallel_safe)]
+    fn combine(
+        current: U256AvgState,
+        other: U256AvgState,
+        _fcinfo: pg_sys::FunctionCallInfo,
+    ) -> U256AvgState {
+        U256AvgState {
+            sum: current.sum + other.sum,
+            count: current.count + other.count,
+        }
+    }
+
+    #[pgrx(immutable, parallel_safe)]
+    fn finalize(
+        current: Self::State,
+        _direct_args: Self::OrderedSetArgs,
+        _fcinfo: pg_sys::FunctionCallInfo,
+    ) -> Option<AnyNumeric> {
+        if current.count == 0 {
+            return None;
+        }
+
+        let sum = AnyNumeric::try_from(current.sum.to_string().as_str())
+            .expect("sum to be valid numeric");
+
+        Some(sum / AnyNumeric::from(current.count))
+    }
+}
+
+/// Net flow of amounts where `incoming` decides whether the amount is added or subtracted.
+pub struct U256SignedSum;
+
+#[pg_aggregate]
+impl Aggregate for U256SignedSum {
+    const NAME: &'static str = "signed_sum";
+    const PARALLEL: Option<ParallelOption> = Some(ParallelOption::Safe);
+    const INITIAL_CONDITION: Option<&'static str> = Some("0");
+
+    type State = I256;
+    type Args = (name!(amount, U256), name!(incoming, bool));
+
+    #[pgrx(immutable, parallel_safe)]
+    fn state(
+        current: Self::State,
+        (amount, incoming): Self::Args,
+        _fcinfo: pg_sys::FunctionCallInfo,
+    ) -> I256 {
+        let amount = primitives::I256::try_from(amount.0).unwrap_or_else(|_| out_of_range("i256"));
+
+        let next = match incoming {
+            true => current.0.checked_add(amount),
+            false => current.0.checked_sub(amount),
+        };
+
+        I256(next.unwrap_or_else(|| out_of_range("i256")))
+    }
+
+    #[pgrx(immutable, parallel_safe)]
+    fn combine(current: I256, other: I256, _fcinfo: pg_sys::FunctionCallInfo) -> I256 {
+        I256(
+            current
+                .0
+                .checked_add(other.0)
+                .unwrap_or_else(|| out_of_range("i256")),
+        )
+    }
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn u256_aggregates() -> Result<()> {
+        Spi::run("CREATE TABLE u256_aggregate_test (amount u256, incoming bool);")?;
+        Spi::run(
+            "INSERT INTO u256_aggregate_test VALUES ('120456', true), ('0x10', false), ('1000', true), (NULL, true);",
+        )?;
+
+        let sum = Spi::get_one::<String>("SELECT sum(amount)::text FROM u256_aggregate_test;")?;
+        assert_eq!(sum, Some(String::from("121472")));
+
+        let min = Spi::get_one::<String>("SELECT min(amount)::text FROM u256_aggregate_test;")?;
+        assert_eq!(min, Some(String::from("16")));
+
+        let max = Spi::get_one::<String>("SELECT max(amount)::text FROM u256_aggregate_test;")?;
+        assert_eq!(max, Some(String::from("120456")));
+
+        let avg = Spi::get_one::<pgrx::AnyNumeric>(
+            "SELECT round(avg(amount), 2) FROM u256_aggregate_test;",
+        )?;
+        assert_eq!(avg, Some(pgrx::AnyNumeric::from_str("40490.67")?));
+
+        let net = Spi::get_one::<String>(
+            "SELECT signed_sum(amount, incoming)::text FROM u256_aggregate_test;",
+        )?;
+        assert_eq!(net, Some(String::from("121440")));
+
+        let empty = Spi::get_one::<String>(
+            "SELECT sum(amount)::text FROM u256_aggregate_test WHERE false;",
+        )?;
+        assert_eq!(empty, None);
+
+        Ok(())
+    }
+}
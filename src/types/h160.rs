@@ -0,0 +1,151 @@
+use pgrx::datum::Internal;
+use pgrx::prelude::*;
+use pgrx::StringInfo;
+
+use alloy::core::hex;
+use alloy::primitives::Address;
+
+use std::ffi::CStr;
+
+use super::{fixed_bytes_datum, recv_bytes, FixedBytes};
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    PostgresType,
+    PostgresEq,
+    PostgresOrd,
+    PostgresHash,
+)]
+#[bikeshed_postgres_type_manually_impl_from_into_datum]
+#[inoutfuncs]
+pub struct H160(pub Address);
+
+impl FixedBytes<20> for H160 {
+    fn from_bytes(bytes: [u8; 20]) -> Self {
+        H160(Address::from(bytes))
+    }
+
+    fn to_bytes(&self) -> [u8; 20] {
+        self.0.into_array()
+    }
+}
+
+fixed_bytes_datum!(H160, 20);
+
+impl InOutFuncs for H160 {
+    fn input(input: &CStr) -> Self {
+        let text = input.to_string_lossy();
+
+        match text.trim().parse::<Address>() {
+            Ok(address) => H160(address),
+            Err(_) => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+                    format!("invalid input syntax for type h160: \"{text}\"")
+                );
+            }
+        }
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        buffer.push_str(&hex::encode_prefixed(self.0));
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn h160_send(value: H160) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn h160_recv(internal: Internal) -> H160 {
+    H160::from_bytes(recv_bytes(internal))
+}
+
+extension_sql!(
+    r#"
+ALTER TYPE H160 SET (SEND = h160_send, RECEIVE = h160_recv);
+"#,
+    name = "h160_binary_io",
+    requires = [h160_send, h160_recv],
+);
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn h160_type_io() -> Result<()> {
+        let lower = Spi::get_one::<String>(
+            "SELECT '1111111111111111111111111111111111111111'::h160::text;",
+        )?;
+
+        assert_eq!(
+            lower,
+            Some(String::from("0x1111111111111111111111111111111111111111"))
+        );
+
+        let mixed = Spi::get_one::<String>(
+            "SELECT '0xA16E02E87B7454126E5E10D957A927A7F5B5D2BE'::h160::text;",
+        )?;
+
+        assert_eq!(
+            mixed,
+            Some(String::from("0xa16e02e87b7454126e5e10d957a927a7f5b5d2be"))
+        );
+
+        let padded = Spi::get_one::<String>(
+            "SELECT ' 0x1111111111111111111111111111111111111111 '::h160::text;",
+        )?;
+
+        assert_eq!(
+            padded,
+            Some(String::from("0x1111111111111111111111111111111111111111"))
+        );
+
+        let sent = Spi::get_one::<Vec<u8>>(
+            "SELECT h160_send('0x1111111111111111111111111111111111111111'::h160);",
+        )?;
+
+        assert_eq!(sent, Some(vec![0x11; 20]));
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn h160_type_compare() -> Result<()> {
+        let equal = Spi::get_one::<bool>(
+            "SELECT '0xabababababababababababababababababababab'::h160 = 'ABABABABABABABABABABABABABABABABABABABAB'::h160;",
+        )?;
+
+        assert_eq!(equal, Some(true));
+
+        let less = Spi::get_one::<bool>(
+            "SELECT '0x1111111111111111111111111111111111111111'::h160 < '0x2222222222222222222222222222222222222222'::h160;",
+        )?;
+
+        assert_eq!(less, Some(true));
+
+        Spi::run("CREATE TABLE h160_index_test (address h160);")?;
+        Spi::run("CREATE INDEX ON h160_index_test USING btree (address);")?;
+        Spi::run("CREATE INDEX ON h160_index_test USING hash (address);")?;
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid input syntax for type h160: \"0x1234\"")]
+    fn h160_type_invalid() {
+        Spi::run("SELECT '0x1234'::h160;").unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use pgrx::datum::Internal;
+use pgrx::prelude::*;
+use pgrx::StringInfo;
+
+use alloy::core::hex;
+use alloy::primitives::{Address, B256};
+
+use std::ffi::CStr;
+
+use super::{fixed_bytes_datum, recv_bytes, FixedBytes, H160};
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    PostgresType,
+    PostgresEq,
+    PostgresOrd,
+    PostgresHash,
+)]
+#[bikeshed_postgres_type_manually_impl_from_into_datum]
+#[inoutfuncs]
+pub struct H256(pub B256);
+
+impl FixedBytes<32> for H256 {
+    fn from_bytes(bytes: [u8; 32]) -> Self {
+        H256(B256::from(bytes))
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        self.0 .0
+    }
+}
+
+fixed_bytes_datum!(H256, 32);
+
+impl InOutFuncs for H256 {
+    fn input(input: &CStr) -> Self {
+        let text = input.to_string_lossy();
+
+        match text.trim().parse::<B256>() {
+            Ok(hash) => H256(hash),
+            Err(_) => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+                    format!("invalid input syntax for type h256: \"{text}\"")
+                );
+            }
+        }
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        buffer.push_str(&hex::encode_prefixed(self.0));
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn h256_send(value: H256) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn h256_recv(internal: Internal) -> H256 {
+    H256::from_bytes(recv_bytes(internal))
+}
+
+extension_sql!(
+    r#"
+ALTER TYPE H256 SET (SEND = h256_send, RECEIVE = h256_recv);
+"#,
+    name = "h256_binary_io",
+    requires = [h256_send, h256_recv],
+);
+
+#[pg_cast(immutable, parallel_safe)]
+fn h256_to_bytea(value: H256) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn bytea_to_h256(bytes: &[u8]) -> H256 {
+    match <[u8; 32]>::try_from(bytes) {
+        Ok(bytes) => H256::from_bytes(bytes),
+        Err(_) => {
+            ereport!(
+                ERROR,
+                PgSqlErrorCode::ERRCODE_INVALID_BINARY_REPRESENTATION,
+                format!(
+                    "invalid length for type h256: expected 32 bytes, got {}",
+                    bytes.len()
+                )
+            );
+        }
+    }
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn h256_to_h160(value: H256) -> H160 {
+    H160(Address::from_word(value.0))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn h256_type_io() -> Result<()> {
+        let hash = Spi::get_one::<String>(
+            "SELECT '1C411E9A96E071241C2F21F7726B17AE89E3CAB4C78BE50E062B03A9FFFBBAD1'::h256::text;",
+        )?;
+
+        assert_eq!(
+            hash,
+            Some(String::from(
+                "0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1"
+            ))
+        );
+
+        let equal = Spi::get_one::<bool>(
+            "SELECT '0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1'::h256 = '1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1'::h256;",
+        )?;
+
+        assert_eq!(equal, Some(true));
+
+        let padded = Spi::get_one::<bool>(
+            "SELECT ' 0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1 '::h256 = '0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1'::h256;",
+        )?;
+
+        assert_eq!(padded, Some(true));
+
+        Spi::run("CREATE TABLE h256_index_test (hash h256);")?;
+        Spi::run("CREATE INDEX ON h256_index_test USING btree (hash);")?;
+        Spi::run("CREATE INDEX ON h256_index_test USING hash (hash);")?;
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn h256_type_casts() -> Result<()> {
+        let address = Spi::get_one::<String>(
+            "SELECT '0x0000000000000000000000001111111111111111111111111111111111111111'::h256::h160::text;",
+        )?;
+
+        assert_eq!(
+            address,
+            Some(String::from("0x1111111111111111111111111111111111111111"))
+        );
+
+        let bytes = Spi::get_one::<Vec<u8>>(
+            "SELECT '0x1111111111111111111111111111111111111111111111111111111111111111'::h256::bytea;",
+        )?;
+
+        assert_eq!(bytes, Some(vec![0x11; 32]));
+
+        let roundtrip = Spi::get_one::<bool>(
+            "SELECT decode(repeat('22', 32), 'hex')::h256 = '0x2222222222222222222222222222222222222222222222222222222222222222'::h256;",
+        )?;
+
+        assert_eq!(roundtrip, Some(true));
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid length for type h256: expected 32 bytes, got 2")]
+    fn h256_type_invalid_length() {
+        Spi::run("SELECT '\\x1234'::bytea::h256;").unwrap();
+    }
+}
@@ -0,0 +1,277 @@
+use pgrx::datum::Internal;
+use pgrx::prelude::*;
+use pgrx::{AnyNumeric, StringInfo};
+
+use alloy::primitives;
+
+use std::ffi::CStr;
+
+use super::u256::{division_by_zero, numeric_digits, out_of_range};
+use super::{fixed_bytes_datum, recv_bytes, FixedBytes, U256};
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    PostgresType,
+    PostgresEq,
+    PostgresOrd,
+    PostgresHash,
+)]
+#[bikeshed_postgres_type_manually_impl_from_into_datum]
+#[inoutfuncs]
+pub struct I256(pub primitives::I256);
+
+impl FixedBytes<32> for I256 {
+    fn from_bytes(bytes: [u8; 32]) -> Self {
+        I256(primitives::I256::from_be_bytes(bytes))
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_be_bytes()
+    }
+}
+
+fixed_bytes_datum!(I256, 32);
+
+impl InOutFuncs for I256 {
+    fn input(input: &CStr) -> Self {
+        let text = input.to_string_lossy();
+
+        match text.trim().parse::<primitives::I256>() {
+            Ok(value) => I256(value),
+            Err(_) => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+                    format!("invalid input syntax for type i256: \"{text}\"")
+                );
+            }
+        }
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        buffer.push_str(&self.0.to_string());
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn i256_send(value: I256) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn i256_recv(internal: Internal) -> I256 {
+    I256::from_bytes(recv_bytes(internal))
+}
+
+extension_sql!(
+    r#"
+ALTER TYPE I256 SET (SEND = i256_send, RECEIVE = i256_recv);
+"#,
+    name = "i256_binary_io",
+    requires = [i256_send, i256_recv],
+);
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(+)]
+#[commutator(+)]
+fn i256_add(left: I256, right: I256) -> I256 {
+    I256(
+        left.0
+            .checked_add(right.0)
+            .unwrap_or_else(|| out_of_range("i256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(-)]
+fn i256_sub(left: I256, right: I256) -> I256 {
+    I256(
+        left.0
+            .checked_sub(right.0)
+            .unwrap_or_else(|| out_of_range("i256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(*)]
+#[commutator(*)]
+fn i256_mul(left: I256, right: I256) -> I256 {
+    I256(
+        left.0
+            .checked_mul(right.0)
+            .unwrap_or_else(|| out_of_range("i256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(/)]
+fn i256_div(left: I256, right: I256) -> I256 {
+    if right.0.is_zero() {
+        division_by_zero();
+    }
+
+    I256(
+        left.0
+            .checked_div(right.0)
+            .unwrap_or_else(|| out_of_range("i256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(%)]
+fn i256_rem(left: I256, right: I256) -> I256 {
+    if right.0.is_zero() {
+        division_by_zero();
+    }
+
+    // MIN % -1 overflows in two's complement but is mathematically zero
+    I256(
+        left.0
+            .checked_rem(right.0)
+            .unwrap_or(primitives::I256::ZERO),
+    )
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn i256_neg(value: I256) -> I256 {
+    I256(
+        value
+            .0
+            .checked_neg()
+            .unwrap_or_else(|| out_of_range("i256")),
+    )
+}
+
+// pgrx operators are always binary, so the prefix minus is declared by hand
+extension_sql!(
+    r#"
+CREATE OPERATOR - (RIGHTARG = I256, FUNCTION = i256_neg);
+"#,
+    name = "i256_neg_operator",
+    requires = [i256_neg],
+);
+
+#[pg_cast(immutable, parallel_safe)]
+fn i256_to_numeric(value: I256) -> AnyNumeric {
+    AnyNumeric::try_from(value.0.to_string().as_str()).expect("i256 to be valid numeric")
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn numeric_to_i256(value: AnyNumeric) -> I256 {
+    I256(
+        numeric_digits(&value, "i256")
+            .parse::<primitives::I256>()
+            .unwrap_or_else(|_| out_of_range("i256")),
+    )
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn i256_to_bigint(value: I256) -> i64 {
+    i64::try_from(value.0).unwrap_or_else(|_| out_of_range("bigint"))
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn bigint_to_i256(value: i64) -> I256 {
+    I256(primitives::I256::try_from(value).expect("i64 to fit in i256"))
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn i256_to_bytea(value: I256) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn bytea_to_i256(bytes: &[u8]) -> I256 {
+    match <[u8; 32]>::try_from(bytes) {
+        Ok(bytes) => I256::from_bytes(bytes),
+        Err(_) => {
+            ereport!(
+                ERROR,
+                PgSqlErrorCode::ERRCODE_INVALID_BINARY_REPRESENTATION,
+                format!(
+                    "invalid length for type i256: expected 32 bytes, got {}",
+                    bytes.len()
+                )
+            );
+        }
+    }
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn i256_to_u256(value: I256) -> U256 {
+    U256(primitives::U256::try_from(value.0).unwrap_or_else(|_| out_of_range("u256")))
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn u256_to_i256(value: U256) -> I256 {
+    I256(primitives::I256::try_from(value.0).unwrap_or_else(|_| out_of_range("i256")))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn i256_type_arithmetic() -> Result<()> {
+        let result = Spi::get_one::<String>(
+            "SELECT ('-500048090940207909755264'::i256 + '242422221263379374'::i256 * '-2'::i256)::text;",
+        )?;
+
+        assert_eq!(result, Some(String::from("-500048575784650436514012")));
+
+        let rem = Spi::get_one::<String>("SELECT (-('5'::i256) % '3'::i256)::text;")?;
+
+        assert_eq!(rem, Some(String::from("-2")));
+
+        let sorted = Spi::get_one::<bool>("SELECT '-1'::i256 < '0'::i256;")?;
+
+        assert_eq!(sorted, Some(true));
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn i256_type_casts() -> Result<()> {
+        let numeric =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT '-242422221263379374'::i256::numeric;")?;
+
+        assert_eq!(
+            numeric,
+            Some(pgrx::AnyNumeric::from_str("-242422221263379374")?)
+        );
+
+        let bigint = Spi::get_one::<i64>(
+            "SELECT decode('fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffdc865', 'hex')::i256::bigint;",
+        )?;
+
+        assert_eq!(bigint, Some(-145307));
+
+        let unsigned = Spi::get_one::<String>("SELECT '42'::i256::u256::text;")?;
+
+        assert_eq!(unsigned, Some(String::from("42")));
+
+        Ok(())
+    }
+
+    #[pg_test(error = "u256 out of range")]
+    fn i256_type_negative_to_u256() {
+        Spi::run("SELECT '-1'::i256::u256;").unwrap();
+    }
+
+    #[pg_test(error = "invalid value for type i256: expected an integer, got -0.25")]
+    fn i256_type_fractional() {
+        Spi::run("SELECT (-0.25)::numeric::i256;").unwrap();
+    }
+}
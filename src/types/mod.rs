@@ -0,0 +1,109 @@
+use pgrx::datum::Internal;
+use pgrx::prelude::*;
+
+mod aggregate;
+mod h160;
+mod h256;
+mod i256;
+mod pubkey;
+mod u256;
+
+pub use h160::H160;
+pub use h256::H256;
+pub use i256::I256;
+pub use pubkey::Pubkey;
+pub use u256::U256;
+
+/// Fixed-width value stored on disk as a plain varlena of `N` raw bytes.
+pub trait FixedBytes<const N: usize>: Sized {
+    fn from_bytes(bytes: [u8; N]) -> Self;
+    fn to_bytes(&self) -> [u8; N];
+}
+
+/// Reads exactly `N` bytes from the `StringInfo` passed to a binary receive function.
+pub fn recv_bytes<const N: usize>(internal: Internal) -> [u8; N] {
+    unsafe {
+        let buf = internal
+            .get_mut::<pg_sys::StringInfoData>()
+            .expect("receive buffer to be present");
+
+        let data = pg_sys::pq_getmsgbytes(buf, N as i32);
+        std::slice::from_raw_parts(data as *const u8, N)
+            .try_into()
+            .expect("message to contain enough bytes")
+    }
+}
+
+/// Implements datum conversion for a [`FixedBytes`] type so it can be used directly in
+/// function signatures instead of going through `PgVarlena`.
+macro_rules! fixed_bytes_datum {
+    ($name:ident, $len:literal) => {
+        impl ::pgrx::datum::IntoDatum for $name {
+            fn into_datum(self) -> Option<::pgrx::pg_sys::Datum> {
+                let bytes = <$name as $crate::types::FixedBytes<$len>>::to_bytes(&self);
+                ::pgrx::datum::IntoDatum::into_datum(&bytes[..])
+            }
+
+            fn type_oid() -> ::pgrx::pg_sys::Oid {
+                ::pgrx::wrappers::rust_regtypein::<Self>()
+            }
+        }
+
+        impl ::pgrx::datum::FromDatum for $name {
+            unsafe fn from_polymorphic_datum(
+                datum: ::pgrx::pg_sys::Datum,
+                is_null: bool,
+                typoid: ::pgrx::pg_sys::Oid,
+            ) -> Option<Self> {
+                <&[u8] as ::pgrx::datum::FromDatum>::from_polymorphic_datum(datum, is_null, typoid)
+                    .map(|bytes| {
+                        <$name as $crate::types::FixedBytes<$len>>::from_bytes(
+                            bytes.try_into().expect(concat!(
+                                stringify!($name),
+                                " to be ",
+                                $len,
+                                " bytes"
+                            )),
+                        )
+                    })
+            }
+        }
+
+        unsafe impl ::pgrx::datum::UnboxDatum for $name {
+            type As<'dat>
+                = Self
+            where
+                Self: 'dat;
+
+            unsafe fn unbox<'dat>(datum: ::pgrx::datum::Datum<'dat>) -> Self::As<'dat>
+            where
+                Self: 'dat,
+            {
+                <Self as ::pgrx::datum::FromDatum>::from_datum(::core::mem::transmute(datum), false)
+                    .unwrap()
+            }
+        }
+
+        unsafe impl<'fcx> ::pgrx::callconv::ArgAbi<'fcx> for $name {
+            unsafe fn unbox_arg_unchecked(arg: ::pgrx::callconv::Arg<'_, 'fcx>) -> Self {
+                let index = arg.index();
+                arg.unbox_arg_using_from_datum()
+                    .unwrap_or_else(|| panic!("argument {index} must not be null"))
+            }
+        }
+
+        unsafe impl ::pgrx::callconv::BoxRet for $name {
+            unsafe fn box_into<'fcx>(
+                self,
+                fcinfo: &mut ::pgrx::callconv::FcInfo<'fcx>,
+            ) -> ::pgrx::datum::Datum<'fcx> {
+                match ::pgrx::datum::IntoDatum::into_datum(self) {
+                    None => fcinfo.return_null(),
+                    Some(datum) => fcinfo.return_raw_datum(datum),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use fixed_bytes_datum;
@@ -0,0 +1,194 @@
+use pgrx::datum::Internal;
+use pgrx::prelude::*;
+use pgrx::StringInfo;
+
+use alloy::primitives::B256;
+use solana_sdk::pubkey;
+
+use std::ffi::CStr;
+
+use super::{fixed_bytes_datum, recv_bytes, FixedBytes, H256};
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    PostgresType,
+    PostgresEq,
+    PostgresOrd,
+    PostgresHash,
+)]
+#[bikeshed_postgres_type_manually_impl_from_into_datum]
+#[inoutfuncs]
+pub struct Pubkey(pub pubkey::Pubkey);
+
+impl FixedBytes<32> for Pubkey {
+    fn from_bytes(bytes: [u8; 32]) -> Self {
+        Pubkey(pubkey::Pubkey::new_from_array(bytes))
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+fixed_bytes_datum!(Pubkey, 32);
+
+impl InOutFuncs for Pubkey {
+    fn input(input: &CStr) -> Self {
+        let text = input.to_string_lossy();
+
+        match text.trim().parse::<pubkey::Pubkey>() {
+            Ok(key) => Pubkey(key),
+            Err(_) => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+                    format!("invalid input syntax for type pubkey: \"{text}\"")
+                );
+            }
+        }
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        buffer.push_str(&self.0.to_string());
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn pubkey_send(value: Pubkey) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn pubkey_recv(internal: Internal) -> Pubkey {
+    Pubkey::from_bytes(recv_bytes(internal))
+}
+
+extension_sql!(
+    r#"
+ALTER TYPE Pubkey SET (SEND = pubkey_send, RECEIVE = pubkey_recv);
+"#,
+    name = "pubkey_binary_io",
+    requires = [pubkey_send, pubkey_recv],
+);
+
+#[pg_cast(immutable, parallel_safe)]
+fn pubkey_to_bytea(value: Pubkey) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn bytea_to_pubkey(bytes: &[u8]) -> Pubkey {
+    match <[u8; 32]>::try_from(bytes) {
+        Ok(bytes) => Pubkey::from_bytes(bytes),
+        Err(_) => {
+            ereport!(
+                ERROR,
+                PgSqlErrorCode::ERRCODE_INVALID_BINARY_REPRESENTATION,
+                format!(
+                    "invalid length for type pubkey: expected 32 bytes, got {}",
+                    bytes.len()
+                )
+            );
+        }
+    }
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn pubkey_to_h256(value: Pubkey) -> H256 {
+    H256(B256::from(value.to_bytes()))
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn h256_to_pubkey(value: H256) -> Pubkey {
+    Pubkey::from_bytes(value.to_bytes())
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn pubkey_type_io() -> Result<()> {
+        let key = Spi::get_one::<String>(
+            "SELECT 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey::text;",
+        )?;
+
+        assert_eq!(
+            key,
+            Some(String::from("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"))
+        );
+
+        let sent = Spi::get_one::<Vec<u8>>(
+            "SELECT pubkey_send('11111111111111111111111111111111'::pubkey);",
+        )?;
+
+        assert_eq!(sent, Some(vec![0; 32]));
+
+        let less = Spi::get_one::<bool>(
+            "SELECT '11111111111111111111111111111111'::pubkey < 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey;",
+        )?;
+
+        assert_eq!(less, Some(true));
+
+        let padded = Spi::get_one::<String>(
+            "SELECT ' TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA '::pubkey::text;",
+        )?;
+
+        assert_eq!(
+            padded,
+            Some(String::from("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"))
+        );
+
+        Spi::run("CREATE TABLE pubkey_index_test (account pubkey);")?;
+        Spi::run("CREATE INDEX ON pubkey_index_test USING btree (account);")?;
+        Spi::run("CREATE INDEX ON pubkey_index_test USING hash (account);")?;
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn pubkey_type_casts() -> Result<()> {
+        let key = Spi::get_one::<String>("SELECT decode(repeat('00', 32), 'hex')::pubkey::text;")?;
+
+        assert_eq!(key, Some(String::from("11111111111111111111111111111111")));
+
+        let hash = Spi::get_one::<String>(
+            "SELECT '11111111111111111111111111111111'::pubkey::h256::text;",
+        )?;
+
+        assert_eq!(
+            hash,
+            Some(String::from(
+                "0x0000000000000000000000000000000000000000000000000000000000000000"
+            ))
+        );
+
+        let roundtrip = Spi::get_one::<bool>(
+            "SELECT 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey::h256::pubkey = 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'::pubkey::bytea::pubkey;",
+        )?;
+
+        assert_eq!(roundtrip, Some(true));
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid input syntax for type pubkey: \"0x1234\"")]
+    fn pubkey_type_invalid() {
+        Spi::run("SELECT '0x1234'::pubkey;").unwrap();
+    }
+
+    #[pg_test(error = "invalid length for type pubkey: expected 32 bytes, got 2")]
+    fn pubkey_type_invalid_length() {
+        Spi::run("SELECT '\\x1234'::bytea::pubkey;").unwrap();
+    }
+}
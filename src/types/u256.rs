@@ -0,0 +1,270 @@
+use pgrx::datum::Internal;
+use pgrx::prelude::*;
+use pgrx::{AnyNumeric, StringInfo};
+
+use alloy::primitives;
+
+use std::ffi::CStr;
+
+use super::{fixed_bytes_datum, recv_bytes, FixedBytes};
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    PostgresType,
+    PostgresEq,
+    PostgresOrd,
+    PostgresHash,
+)]
+#[bikeshed_postgres_type_manually_impl_from_into_datum]
+#[inoutfuncs]
+pub struct U256(pub primitives::U256);
+
+impl FixedBytes<32> for U256 {
+    fn from_bytes(bytes: [u8; 32]) -> Self {
+        U256(primitives::U256::from_be_bytes(bytes))
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_be_bytes()
+    }
+}
+
+fixed_bytes_datum!(U256, 32);
+
+impl InOutFuncs for U256 {
+    fn input(input: &CStr) -> Self {
+        let text = input.to_string_lossy();
+
+        match text.trim().parse::<primitives::U256>() {
+            Ok(value) => U256(value),
+            Err(_) => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+                    format!("invalid input syntax for type u256: \"{text}\"")
+                );
+            }
+        }
+    }
+
+    fn output(&self, buffer: &mut StringInfo) {
+        buffer.push_str(&self.0.to_string());
+    }
+}
+
+pub fn out_of_range(type_name: &str) -> ! {
+    ereport!(
+        ERROR,
+        PgSqlErrorCode::ERRCODE_NUMERIC_VALUE_OUT_OF_RANGE,
+        format!("{type_name} out of range")
+    );
+}
+
+/// Digits of an integral numeric, fractional values are rejected instead of being rounded.
+pub fn numeric_digits<'a>(value: &'a AnyNumeric, type_name: &str) -> &'a str {
+    let digits = value.normalize();
+
+    if digits.contains('.') {
+        ereport!(
+            ERROR,
+            PgSqlErrorCode::ERRCODE_INVALID_PARAMETER_VALUE,
+            format!("invalid value for type {type_name}: expected an integer, got {digits}")
+        );
+    }
+
+    digits
+}
+
+pub fn division_by_zero() -> ! {
+    ereport!(
+        ERROR,
+        PgSqlErrorCode::ERRCODE_DIVISION_BY_ZERO,
+        "division by zero"
+    );
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn u256_send(value: U256) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_extern(immutable, parallel_safe)]
+fn u256_recv(internal: Internal) -> U256 {
+    U256::from_bytes(recv_bytes(internal))
+}
+
+extension_sql!(
+    r#"
+ALTER TYPE U256 SET (SEND = u256_send, RECEIVE = u256_recv);
+"#,
+    name = "u256_binary_io",
+    requires = [u256_send, u256_recv],
+);
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(+)]
+#[commutator(+)]
+fn u256_add(left: U256, right: U256) -> U256 {
+    U256(
+        left.0
+            .checked_add(right.0)
+            .unwrap_or_else(|| out_of_range("u256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(-)]
+fn u256_sub(left: U256, right: U256) -> U256 {
+    U256(
+        left.0
+            .checked_sub(right.0)
+            .unwrap_or_else(|| out_of_range("u256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(*)]
+#[commutator(*)]
+fn u256_mul(left: U256, right: U256) -> U256 {
+    U256(
+        left.0
+            .checked_mul(right.0)
+            .unwrap_or_else(|| out_of_range("u256")),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(/)]
+fn u256_div(left: U256, right: U256) -> U256 {
+    U256(
+        left.0
+            .checked_div(right.0)
+            .unwrap_or_else(|| division_by_zero()),
+    )
+}
+
+#[pg_operator(immutable, parallel_safe)]
+#[opname(%)]
+fn u256_rem(left: U256, right: U256) -> U256 {
+    U256(
+        left.0
+            .checked_rem(right.0)
+            .unwrap_or_else(|| division_by_zero()),
+    )
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn u256_to_numeric(value: U256) -> AnyNumeric {
+    AnyNumeric::try_from(value.0.to_string().as_str()).expect("u256 to be valid numeric")
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn numeric_to_u256(value: AnyNumeric) -> U256 {
+    U256(
+        numeric_digits(&value, "u256")
+            .parse::<primitives::U256>()
+            .unwrap_or_else(|_| out_of_range("u256")),
+    )
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn u256_to_bigint(value: U256) -> i64 {
+    i64::try_from(value.0).unwrap_or_else(|_| out_of_range("bigint"))
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn bigint_to_u256(value: i64) -> U256 {
+    U256(primitives::U256::try_from(value).unwrap_or_else(|_| out_of_range("u256")))
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn u256_to_bytea(value: U256) -> Vec<u8> {
+    value.to_bytes().to_vec()
+}
+
+#[pg_cast(immutable, parallel_safe)]
+fn bytea_to_u256(bytes: &[u8]) -> U256 {
+    U256(primitives::U256::try_from_be_slice(bytes).unwrap_or_else(|| out_of_range("u256")))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn u256_type_arithmetic() -> Result<()> {
+        let sum = Spi::get_one::<String>(
+            "SELECT ('115792089237316195423570985008687907853269984665640564039457584007913129639934'::u256 + '0x1'::u256)::text;",
+        )?;
+
+        assert_eq!(
+            sum,
+            Some(String::from(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            ))
+        );
+
+        let result = Spi::get_one::<String>(
+            "SELECT ((1000::bigint::u256 * 3::bigint::u256 - 1::bigint::u256) / 7::bigint::u256 % 100::bigint::u256)::text;",
+        )?;
+
+        assert_eq!(result, Some(String::from("28")));
+
+        let sorted = Spi::get_one::<bool>("SELECT '0xff'::u256 > '254'::u256;")?;
+
+        assert_eq!(sorted, Some(true));
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn u256_type_casts() -> Result<()> {
+        let numeric =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT '500048090940207909755264'::u256::numeric;")?;
+
+        assert_eq!(
+            numeric,
+            Some(pgrx::AnyNumeric::from_str("500048090940207909755264")?)
+        );
+
+        let from_numeric = Spi::get_one::<String>("SELECT 120456.00::numeric::u256::text;")?;
+
+        assert_eq!(from_numeric, Some(String::from("120456")));
+
+        let bigint = Spi::get_one::<i64>("SELECT '\\x01d688'::bytea::u256::bigint;")?;
+
+        assert_eq!(bigint, Some(120456));
+
+        Ok(())
+    }
+
+    #[pg_test(error = "u256 out of range")]
+    fn u256_type_overflow() {
+        Spi::run(
+            "SELECT '115792089237316195423570985008687907853269984665640564039457584007913129639935'::u256 + '1'::u256;",
+        )
+        .unwrap();
+    }
+
+    #[pg_test(error = "invalid value for type u256: expected an integer, got 1.5")]
+    fn u256_type_fractional() {
+        Spi::run("SELECT 1.5::numeric::u256;").unwrap();
+    }
+
+    #[pg_test(error = "division by zero")]
+    fn u256_type_division_by_zero() {
+        Spi::run("SELECT '1'::u256 / '0'::u256;").unwrap();
+    }
+}
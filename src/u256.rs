@@ -1,21 +1,101 @@
 use pgrx::prelude::*;
 
+use alloy::primitives;
+
+use crate::decode::{Error, Result};
+
 #[pg_schema]
 #[allow(non_snake_case)]
 mod U256 {
-    use alloy::core::primitives::U256;
-
     use pgrx::prelude::*;
 
+    use super::parse;
+    use crate::decode::{self, OrReport};
+    use crate::types;
+
     #[pg_extern(name = "parse", immutable, parallel_safe)]
     fn parse_u256(string: &str) -> pgrx::AnyNumeric {
-        pgrx::AnyNumeric::try_from(
-            string
-                .parse::<U256>()
-                .expect("string is U256")
-                .to_string()
-                .as_str(),
-        )
-        .expect("can be converted to numeric")
+        decode::numeric(parse(string).or_report())
+    }
+
+    #[pg_extern(name = "try_parse", immutable, parallel_safe)]
+    fn try_parse_u256(string: &str) -> Option<pgrx::AnyNumeric> {
+        parse(string).ok().map(decode::numeric)
+    }
+
+    #[pg_extern(name = "to_hex", immutable, parallel_safe)]
+    fn to_hex_u256(value: types::U256) -> String {
+        format!("{:#x}", value.0)
+    }
+
+    #[pg_extern(name = "checked_add", immutable, parallel_safe)]
+    fn checked_add_u256(left: types::U256, right: types::U256) -> Option<types::U256> {
+        left.0.checked_add(right.0).map(types::U256)
+    }
+
+    #[pg_extern(name = "checked_sub", immutable, parallel_safe)]
+    fn checked_sub_u256(left: types::U256, right: types::U256) -> Option<types::U256> {
+        left.0.checked_sub(right.0).map(types::U256)
+    }
+
+    #[pg_extern(name = "checked_mul", immutable, parallel_safe)]
+    fn checked_mul_u256(left: types::U256, right: types::U256) -> Option<types::U256> {
+        left.0.checked_mul(right.0).map(types::U256)
+    }
+
+    #[pg_extern(name = "checked_div", immutable, parallel_safe)]
+    fn checked_div_u256(left: types::U256, right: types::U256) -> Option<types::U256> {
+        left.0.checked_div(right.0).map(types::U256)
+    }
+
+    #[pg_extern(name = "wrapping_add", immutable, parallel_safe)]
+    fn wrapping_add_u256(left: types::U256, right: types::U256) -> types::U256 {
+        types::U256(left.0.wrapping_add(right.0))
+    }
+
+    #[pg_extern(name = "wrapping_sub", immutable, parallel_safe)]
+    fn wrapping_sub_u256(left: types::U256, right: types::U256) -> types::U256 {
+        types::U256(left.0.wrapping_sub(right.0))
+    }
+
+    #[pg_extern(name = "wrapping_mul", immutable, parallel_safe)]
+    fn wrapping_mul_u256(left: types::U256, right: types::U256) -> types::U256 {
+        types::U256(left.0.wrapping_mul(right.0))
+    }
+}
+
+pub fn parse(string: &str) -> Result<primitives::U256> {
+    string
+        .parse()
+        .map_err(|_| Error::invalid_text("u256", string))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    use anyhow::Result;
+
+    #[pg_test]
+    fn u256_checked_wrapping() -> Result<()> {
+        let max = "'115792089237316195423570985008687907853269984665640564039457584007913129639935'::u256";
+
+        let checked =
+            Spi::get_one::<String>(&format!("SELECT U256.checked_add({max}, '1'::u256)::text;"))?;
+
+        assert_eq!(checked, None);
+
+        let wrapped = Spi::get_one::<String>(&format!(
+            "SELECT U256.wrapping_add({max}, '2'::u256)::text;"
+        ))?;
+
+        assert_eq!(wrapped, Some(String::from("1")));
+
+        let hex = Spi::get_one::<String>("SELECT U256.to_hex('120456'::u256);")?;
+
+        assert_eq!(hex, Some(String::from("0x1d688")));
+
+        Ok(())
     }
 }
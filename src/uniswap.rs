@@ -1,21 +1,25 @@
 use pgrx::prelude::*;
 
-use num::{
-    bigint::{Sign, ToBigInt},
-    rational::Ratio,
-    BigInt, BigRational,
-};
+use num::{bigint::Sign, BigInt, Signed, Zero};
 
 use bigdecimal::BigDecimal;
 
-use anyhow::Result;
+use std::str::FromStr;
+
+use alloy::core::hex;
+use alloy::primitives::{aliases::U24, b256, keccak256, Address, B256, U256};
+
+use crate::decode::{self, Error, Result, Topics, With};
+use crate::h160::sort_tokens;
+use crate::liquidity_amounts;
+use crate::tick_math::{self, MAX_TICK, MIN_TICK};
+use crate::u256;
 
 pub enum SwapAction {
     SELL = -1,
     BUY = 1,
 }
 
-#[allow(dead_code)]
 pub struct Swap {
     pub action: SwapAction,
     pub base_amount: BigInt,
@@ -27,84 +31,962 @@ pub struct Sync {
     pub quote_reserve: BigInt,
 }
 
+/// How fixed point results are turned into integers, `down` matches what the pool contracts do.
+#[derive(Clone, Copy)]
+pub enum Rounding {
+    Down,
+    Up,
+    Nearest,
+}
+
+impl Rounding {
+    fn parse(input: &str) -> Result<Self> {
+        match input {
+            "down" => Ok(Rounding::Down),
+            "up" => Ok(Rounding::Up),
+            "nearest" => Ok(Rounding::Nearest),
+            _ => Err(Error::invalid_text("rounding", input)),
+        }
+    }
+
+    /// Divides two non negative integers.
+    fn div(self, numerator: BigInt, denominator: &BigInt) -> BigInt {
+        match self {
+            Rounding::Down => numerator / denominator,
+            Rounding::Up => (numerator + denominator - 1) / denominator,
+            Rounding::Nearest => (numerator * 2 + denominator) / (denominator * 2),
+        }
+    }
+}
+
+/// Liquidity added by a Mint or removed by a Burn, with the token amounts it was worth.
+pub struct PositionChange {
+    pub liquidity: BigInt,
+    pub amount0: BigInt,
+    pub amount1: BigInt,
+}
+
+pub struct Collect {
+    pub amount0: BigInt,
+    pub amount1: BigInt,
+}
+
 #[pg_schema]
 #[allow(non_snake_case)]
 mod Uniswap {
     use pgrx::prelude::*;
 
-    use std::error::Error;
-    use std::str::FromStr;
-
     use alloy::core::hex;
-    use num::{BigInt, Signed};
+    use num::{bigint::Sign, BigInt, Signed};
+
+    use super::{
+        amounts_for_liquidity, collect_row, decode_burn, decode_collect, decode_mint, decode_swap,
+        decode_sync, liquidity_for_amounts, pool_for, position_row, position_tick, price_to_tick,
+        sqrt_price_to_price, swap_liquidity, swap_row, swap_tick, sync_price, sync_row, Rounding,
+    };
+    use crate::decode::{self, OrReport, Topics};
+    use crate::h160::sort_tokens;
+    use crate::tick_math;
+    use crate::u256;
+
+    extension_sql!(
+        r#"
+CREATE TYPE Uniswap.swap AS (
+    action integer,
+    base_amount numeric,
+    quote_amount numeric,
+    sqrt_price_x96 numeric,
+    liquidity numeric,
+    tick integer
+);
+
+CREATE TYPE Uniswap.sync AS (
+    base_reserve numeric,
+    quote_reserve numeric
+);
+
+CREATE TYPE Uniswap.position AS (
+    owner text,
+    tick_lower integer,
+    tick_upper integer,
+    liquidity numeric,
+    amount0 numeric,
+    amount1 numeric
+);
+
+CREATE TYPE Uniswap.collect AS (
+    owner text,
+    tick_lower integer,
+    tick_upper integer,
+    amount0 numeric,
+    amount1 numeric
+);
+"#,
+        name = "uniswap_types",
+    );
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_swap(data: &str) -> pgrx::composite_type!('static, "Uniswap.swap") {
+        swap_row(&decode::hex(data).or_report()).or_report()
+    }
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_swap_bytea(data: &[u8]) -> pgrx::composite_type!('static, "Uniswap.swap") {
+        swap_row(data).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_swap(data: &str) -> Option<pgrx::composite_type!('static, "Uniswap.swap")> {
+        swap_row(&decode::hex(data).ok()?).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_swap_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.swap")> {
+        swap_row(data).ok()
+    }
+
+    #[pg_extern(name = "decode_sync", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_sync(
+        data: &str,
+        rounding: default!(&str, "'down'"),
+    ) -> pgrx::composite_type!('static, "Uniswap.sync") {
+        let rounding = Rounding::parse(rounding).or_report();
+        sync_row(decode_sync(&decode::hex(data).or_report(), rounding).or_report())
+    }
+
+    #[pg_extern(name = "decode_sync", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_sync_bytea(
+        data: &[u8],
+        rounding: default!(&str, "'down'"),
+    ) -> pgrx::composite_type!('static, "Uniswap.sync") {
+        let rounding = Rounding::parse(rounding).or_report();
+        sync_row(decode_sync(data, rounding).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_sync",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_sync(
+        data: &str,
+        rounding: default!(&str, "'down'"),
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.sync")> {
+        let rounding = Rounding::parse(rounding).ok()?;
+        decode_sync(&decode::hex(data).ok()?, rounding)
+            .ok()
+            .map(sync_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_sync",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_sync_bytea(
+        data: &[u8],
+        rounding: default!(&str, "'down'"),
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.sync")> {
+        let rounding = Rounding::parse(rounding).ok()?;
+        decode_sync(data, rounding).ok().map(sync_row)
+    }
 
-    use super::decode_swap;
-    use super::decode_sync;
-    use super::sync_price;
+    #[pg_extern(name = "decode_mint", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_mint(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "Uniswap.position") {
+        let mint = decode_mint(&decode::hex(data).or_report()).or_report();
+        position_row(&topics, mint).or_report()
+    }
+
+    #[pg_extern(name = "decode_mint", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_mint_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "Uniswap.position") {
+        let mint = decode_mint(data).or_report();
+        position_row(&topics, mint).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_mint",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_mint(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.position")> {
+        let mint = decode_mint(&decode::hex(data).ok()?).ok()?;
+        position_row(&topics, mint).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_mint",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_mint_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.position")> {
+        let mint = decode_mint(data).ok()?;
+        position_row(&topics, mint).ok()
+    }
+
+    #[pg_extern(name = "decode_burn", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_burn(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "Uniswap.position") {
+        let burn = decode_burn(&decode::hex(data).or_report()).or_report();
+        position_row(&topics, burn).or_report()
+    }
+
+    #[pg_extern(name = "decode_burn", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_burn_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "Uniswap.position") {
+        let burn = decode_burn(data).or_report();
+        position_row(&topics, burn).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_burn",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_burn(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.position")> {
+        let burn = decode_burn(&decode::hex(data).ok()?).ok()?;
+        position_row(&topics, burn).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_burn",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_burn_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.position")> {
+        let burn = decode_burn(data).ok()?;
+        position_row(&topics, burn).ok()
+    }
+
+    #[pg_extern(name = "decode_collect", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_collect(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "Uniswap.collect") {
+        let collect = decode_collect(&decode::hex(data).or_report()).or_report();
+        collect_row(&topics, collect).or_report()
+    }
+
+    #[pg_extern(name = "decode_collect", immutable, parallel_safe, requires = ["uniswap_types"])]
+    fn uni_decode_collect_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "Uniswap.collect") {
+        let collect = decode_collect(data).or_report();
+        collect_row(&topics, collect).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_collect",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_collect(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.collect")> {
+        let collect = decode_collect(&decode::hex(data).ok()?).ok()?;
+        collect_row(&topics, collect).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_collect",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_types"]
+    )]
+    fn uni_try_decode_collect_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Uniswap.collect")> {
+        let collect = decode_collect(data).ok()?;
+        collect_row(&topics, collect).ok()
+    }
+
+    #[pg_extern(name = "swap_sender", immutable, parallel_safe)]
+    fn uni_swap_sender(topics: Array<&str>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "swap_sender_raw", immutable, parallel_safe)]
+    fn uni_swap_sender_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_sender", immutable, parallel_safe)]
+    fn uni_try_swap_sender(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_swap_sender_raw", immutable, parallel_safe)]
+    fn uni_try_swap_sender_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "swap_recipient", immutable, parallel_safe)]
+    fn uni_swap_recipient(topics: Array<&str>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "swap_recipient_raw", immutable, parallel_safe)]
+    fn uni_swap_recipient_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_recipient", immutable, parallel_safe)]
+    fn uni_try_swap_recipient(topics: Array<&str>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_swap_recipient_raw", immutable, parallel_safe)]
+    fn uni_try_swap_recipient_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
 
     #[pg_extern(name = "swap_type", immutable, parallel_safe)]
     fn uni_swap_type(data: &str) -> i32 {
-        decode_swap(&hex::decode(data).unwrap()).unwrap().action as i32
+        decode_swap(&decode::hex(data).or_report())
+            .or_report()
+            .action as i32
+    }
+
+    #[pg_extern(name = "swap_type", immutable, parallel_safe)]
+    fn uni_swap_type_bytea(data: &[u8]) -> i32 {
+        decode_swap(data).or_report().action as i32
+    }
+
+    #[pg_extern(name = "try_swap_type", immutable, parallel_safe)]
+    fn uni_try_swap_type(data: &str) -> Option<i32> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(swap.action as i32)
+    }
+
+    #[pg_extern(name = "try_swap_type", immutable, parallel_safe)]
+    fn uni_try_swap_type_bytea(data: &[u8]) -> Option<i32> {
+        let swap = decode_swap(data).ok()?;
+        Some(swap.action as i32)
     }
 
     #[pg_extern(name = "swap_base_amount", immutable, parallel_safe)]
-    fn uni_swap_base_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        let amount = BigInt::from_signed_bytes_be(&hex::decode(data).unwrap()[64..96]);
+    fn uni_swap_base_amount(data: &str) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(swap.base_amount.abs())
+    }
 
-        Ok(pgrx::AnyNumeric::from_str(
-            amount.abs().to_string().as_str(),
-        )?)
+    #[pg_extern(name = "swap_base_amount", immutable, parallel_safe)]
+    fn uni_swap_base_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(swap.base_amount.abs())
+    }
+
+    #[pg_extern(name = "try_swap_base_amount", immutable, parallel_safe)]
+    fn uni_try_swap_base_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(swap.base_amount.abs()))
+    }
+
+    #[pg_extern(name = "try_swap_base_amount", immutable, parallel_safe)]
+    fn uni_try_swap_base_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(swap.base_amount.abs()))
     }
 
     #[pg_extern(name = "swap_quote_amount", immutable, parallel_safe)]
-    fn uni_swap_quote_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        let amount = BigInt::from_signed_bytes_be(&hex::decode(data).unwrap()[96..128]);
+    fn uni_swap_quote_amount(data: &str) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(swap.quote_amount.abs())
+    }
+
+    #[pg_extern(name = "swap_quote_amount", immutable, parallel_safe)]
+    fn uni_swap_quote_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(swap.quote_amount.abs())
+    }
 
-        Ok(pgrx::AnyNumeric::from_str(
-            amount.abs().to_string().as_str(),
-        )?)
+    #[pg_extern(name = "try_swap_quote_amount", immutable, parallel_safe)]
+    fn uni_try_swap_quote_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(swap.quote_amount.abs()))
+    }
+
+    #[pg_extern(name = "try_swap_quote_amount", immutable, parallel_safe)]
+    fn uni_try_swap_quote_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(swap.quote_amount.abs()))
     }
 
     #[pg_extern(name = "sync_base_reserve", immutable, parallel_safe)]
-    fn uni_sync_base_reserve(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_sync(&hex::decode(data).unwrap())
-                .unwrap()
-                .base_reserve
-                .to_string()
-                .as_str(),
-        )?)
+    fn uni_sync_base_reserve(data: &str, rounding: default!(&str, "'down'")) -> pgrx::AnyNumeric {
+        let rounding = Rounding::parse(rounding).or_report();
+        let sync = decode_sync(&decode::hex(data).or_report(), rounding).or_report();
+        decode::numeric(sync.base_reserve)
+    }
+
+    #[pg_extern(name = "sync_base_reserve", immutable, parallel_safe)]
+    fn uni_sync_base_reserve_bytea(
+        data: &[u8],
+        rounding: default!(&str, "'down'"),
+    ) -> pgrx::AnyNumeric {
+        let rounding = Rounding::parse(rounding).or_report();
+        let sync = decode_sync(data, rounding).or_report();
+        decode::numeric(sync.base_reserve)
+    }
+
+    #[pg_extern(name = "try_sync_base_reserve", immutable, parallel_safe)]
+    fn uni_try_sync_base_reserve(
+        data: &str,
+        rounding: default!(&str, "'down'"),
+    ) -> Option<pgrx::AnyNumeric> {
+        let rounding = Rounding::parse(rounding).ok()?;
+        let sync = decode_sync(&decode::hex(data).ok()?, rounding).ok()?;
+        Some(decode::numeric(sync.base_reserve))
+    }
+
+    #[pg_extern(name = "try_sync_base_reserve", immutable, parallel_safe)]
+    fn uni_try_sync_base_reserve_bytea(
+        data: &[u8],
+        rounding: default!(&str, "'down'"),
+    ) -> Option<pgrx::AnyNumeric> {
+        let rounding = Rounding::parse(rounding).ok()?;
+        let sync = decode_sync(data, rounding).ok()?;
+        Some(decode::numeric(sync.base_reserve))
+    }
+
+    #[pg_extern(name = "sync_quote_reserve", immutable, parallel_safe)]
+    fn uni_sync_quote_reserve(data: &str, rounding: default!(&str, "'down'")) -> pgrx::AnyNumeric {
+        let rounding = Rounding::parse(rounding).or_report();
+        let sync = decode_sync(&decode::hex(data).or_report(), rounding).or_report();
+        decode::numeric(sync.quote_reserve)
     }
 
     #[pg_extern(name = "sync_quote_reserve", immutable, parallel_safe)]
-    fn uni_swap_quote_reserve(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_sync(&hex::decode(data).unwrap())
-                .unwrap()
-                .quote_reserve
-                .to_string()
-                .as_str(),
-        )?)
+    fn uni_sync_quote_reserve_bytea(
+        data: &[u8],
+        rounding: default!(&str, "'down'"),
+    ) -> pgrx::AnyNumeric {
+        let rounding = Rounding::parse(rounding).or_report();
+        let sync = decode_sync(data, rounding).or_report();
+        decode::numeric(sync.quote_reserve)
+    }
+
+    #[pg_extern(name = "try_sync_quote_reserve", immutable, parallel_safe)]
+    fn uni_try_sync_quote_reserve(
+        data: &str,
+        rounding: default!(&str, "'down'"),
+    ) -> Option<pgrx::AnyNumeric> {
+        let rounding = Rounding::parse(rounding).ok()?;
+        let sync = decode_sync(&decode::hex(data).ok()?, rounding).ok()?;
+        Some(decode::numeric(sync.quote_reserve))
+    }
+
+    #[pg_extern(name = "try_sync_quote_reserve", immutable, parallel_safe)]
+    fn uni_try_sync_quote_reserve_bytea(
+        data: &[u8],
+        rounding: default!(&str, "'down'"),
+    ) -> Option<pgrx::AnyNumeric> {
+        let rounding = Rounding::parse(rounding).ok()?;
+        let sync = decode_sync(data, rounding).ok()?;
+        Some(decode::numeric(sync.quote_reserve))
+    }
+
+    #[pg_extern(name = "swap_liquidity", immutable, parallel_safe)]
+    fn uni_swap_liquidity(data: &str) -> pgrx::AnyNumeric {
+        decode::numeric(swap_liquidity(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "swap_liquidity", immutable, parallel_safe)]
+    fn uni_swap_liquidity_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        decode::numeric(swap_liquidity(data).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_liquidity", immutable, parallel_safe)]
+    fn uni_try_swap_liquidity(data: &str) -> Option<pgrx::AnyNumeric> {
+        let liquidity = swap_liquidity(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(liquidity))
+    }
+
+    #[pg_extern(name = "try_swap_liquidity", immutable, parallel_safe)]
+    fn uni_try_swap_liquidity_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let liquidity = swap_liquidity(data).ok()?;
+        Some(decode::numeric(liquidity))
+    }
+
+    #[pg_extern(name = "sync_price", immutable, parallel_safe)]
+    fn uni_sync_price(data: &str, base_decimals: i64, quote_decimals: i64) -> pgrx::AnyNumeric {
+        let price = sync_price(
+            &decode::hex(data).or_report(),
+            base_decimals,
+            quote_decimals,
+        )
+        .or_report();
+
+        decode::numeric(price)
     }
 
     #[pg_extern(name = "sync_price", immutable, parallel_safe)]
-    fn uni_sync_price(
+    fn uni_sync_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> pgrx::AnyNumeric {
+        let price = sync_price(data, base_decimals, quote_decimals).or_report();
+
+        decode::numeric(price)
+    }
+
+    #[pg_extern(name = "try_sync_price", immutable, parallel_safe)]
+    fn uni_try_sync_price(
         data: &str,
         base_decimals: i64,
         quote_decimals: i64,
-    ) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            sync_price(&hex::decode(data).unwrap(), base_decimals, quote_decimals)
-                .to_string()
-                .as_str(),
-        )?)
+    ) -> Option<pgrx::AnyNumeric> {
+        let price = sync_price(&decode::hex(data).ok()?, base_decimals, quote_decimals).ok()?;
+        Some(decode::numeric(price))
+    }
+
+    #[pg_extern(name = "try_sync_price", immutable, parallel_safe)]
+    fn uni_try_sync_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let price = sync_price(data, base_decimals, quote_decimals).ok()?;
+        Some(decode::numeric(price))
+    }
+
+    #[pg_extern(name = "mint_liquidity", immutable, parallel_safe)]
+    fn uni_mint_liquidity(data: &str) -> pgrx::AnyNumeric {
+        let mint = decode_mint(&decode::hex(data).or_report()).or_report();
+        decode::numeric(mint.liquidity)
+    }
+
+    #[pg_extern(name = "mint_liquidity", immutable, parallel_safe)]
+    fn uni_mint_liquidity_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let mint = decode_mint(data).or_report();
+        decode::numeric(mint.liquidity)
+    }
+
+    #[pg_extern(name = "try_mint_liquidity", immutable, parallel_safe)]
+    fn uni_try_mint_liquidity(data: &str) -> Option<pgrx::AnyNumeric> {
+        let mint = decode_mint(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(mint.liquidity))
+    }
+
+    #[pg_extern(name = "try_mint_liquidity", immutable, parallel_safe)]
+    fn uni_try_mint_liquidity_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let mint = decode_mint(data).ok()?;
+        Some(decode::numeric(mint.liquidity))
+    }
+
+    #[pg_extern(name = "mint_amount0", immutable, parallel_safe)]
+    fn uni_mint_amount0(data: &str) -> pgrx::AnyNumeric {
+        let mint = decode_mint(&decode::hex(data).or_report()).or_report();
+        decode::numeric(mint.amount0)
+    }
+
+    #[pg_extern(name = "mint_amount0", immutable, parallel_safe)]
+    fn uni_mint_amount0_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let mint = decode_mint(data).or_report();
+        decode::numeric(mint.amount0)
+    }
+
+    #[pg_extern(name = "try_mint_amount0", immutable, parallel_safe)]
+    fn uni_try_mint_amount0(data: &str) -> Option<pgrx::AnyNumeric> {
+        let mint = decode_mint(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(mint.amount0))
+    }
+
+    #[pg_extern(name = "try_mint_amount0", immutable, parallel_safe)]
+    fn uni_try_mint_amount0_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let mint = decode_mint(data).ok()?;
+        Some(decode::numeric(mint.amount0))
+    }
+
+    #[pg_extern(name = "mint_amount1", immutable, parallel_safe)]
+    fn uni_mint_amount1(data: &str) -> pgrx::AnyNumeric {
+        let mint = decode_mint(&decode::hex(data).or_report()).or_report();
+        decode::numeric(mint.amount1)
+    }
+
+    #[pg_extern(name = "mint_amount1", immutable, parallel_safe)]
+    fn uni_mint_amount1_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let mint = decode_mint(data).or_report();
+        decode::numeric(mint.amount1)
+    }
+
+    #[pg_extern(name = "try_mint_amount1", immutable, parallel_safe)]
+    fn uni_try_mint_amount1(data: &str) -> Option<pgrx::AnyNumeric> {
+        let mint = decode_mint(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(mint.amount1))
+    }
+
+    #[pg_extern(name = "try_mint_amount1", immutable, parallel_safe)]
+    fn uni_try_mint_amount1_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let mint = decode_mint(data).ok()?;
+        Some(decode::numeric(mint.amount1))
+    }
+
+    #[pg_extern(name = "burn_liquidity", immutable, parallel_safe)]
+    fn uni_burn_liquidity(data: &str) -> pgrx::AnyNumeric {
+        let burn = decode_burn(&decode::hex(data).or_report()).or_report();
+        decode::numeric(burn.liquidity)
+    }
+
+    #[pg_extern(name = "burn_liquidity", immutable, parallel_safe)]
+    fn uni_burn_liquidity_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let burn = decode_burn(data).or_report();
+        decode::numeric(burn.liquidity)
+    }
+
+    #[pg_extern(name = "try_burn_liquidity", immutable, parallel_safe)]
+    fn uni_try_burn_liquidity(data: &str) -> Option<pgrx::AnyNumeric> {
+        let burn = decode_burn(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(burn.liquidity))
+    }
+
+    #[pg_extern(name = "try_burn_liquidity", immutable, parallel_safe)]
+    fn uni_try_burn_liquidity_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let burn = decode_burn(data).ok()?;
+        Some(decode::numeric(burn.liquidity))
+    }
+
+    #[pg_extern(name = "burn_amount0", immutable, parallel_safe)]
+    fn uni_burn_amount0(data: &str) -> pgrx::AnyNumeric {
+        let burn = decode_burn(&decode::hex(data).or_report()).or_report();
+        decode::numeric(burn.amount0)
+    }
+
+    #[pg_extern(name = "burn_amount0", immutable, parallel_safe)]
+    fn uni_burn_amount0_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let burn = decode_burn(data).or_report();
+        decode::numeric(burn.amount0)
+    }
+
+    #[pg_extern(name = "try_burn_amount0", immutable, parallel_safe)]
+    fn uni_try_burn_amount0(data: &str) -> Option<pgrx::AnyNumeric> {
+        let burn = decode_burn(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(burn.amount0))
+    }
+
+    #[pg_extern(name = "try_burn_amount0", immutable, parallel_safe)]
+    fn uni_try_burn_amount0_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let burn = decode_burn(data).ok()?;
+        Some(decode::numeric(burn.amount0))
+    }
+
+    #[pg_extern(name = "burn_amount1", immutable, parallel_safe)]
+    fn uni_burn_amount1(data: &str) -> pgrx::AnyNumeric {
+        let burn = decode_burn(&decode::hex(data).or_report()).or_report();
+        decode::numeric(burn.amount1)
+    }
+
+    #[pg_extern(name = "burn_amount1", immutable, parallel_safe)]
+    fn uni_burn_amount1_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let burn = decode_burn(data).or_report();
+        decode::numeric(burn.amount1)
+    }
+
+    #[pg_extern(name = "try_burn_amount1", immutable, parallel_safe)]
+    fn uni_try_burn_amount1(data: &str) -> Option<pgrx::AnyNumeric> {
+        let burn = decode_burn(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(burn.amount1))
+    }
+
+    #[pg_extern(name = "try_burn_amount1", immutable, parallel_safe)]
+    fn uni_try_burn_amount1_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let burn = decode_burn(data).ok()?;
+        Some(decode::numeric(burn.amount1))
+    }
+
+    #[pg_extern(name = "collect_amount0", immutable, parallel_safe)]
+    fn uni_collect_amount0(data: &str) -> pgrx::AnyNumeric {
+        let collect = decode_collect(&decode::hex(data).or_report()).or_report();
+        decode::numeric(collect.amount0)
+    }
+
+    #[pg_extern(name = "collect_amount0", immutable, parallel_safe)]
+    fn uni_collect_amount0_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let collect = decode_collect(data).or_report();
+        decode::numeric(collect.amount0)
+    }
+
+    #[pg_extern(name = "try_collect_amount0", immutable, parallel_safe)]
+    fn uni_try_collect_amount0(data: &str) -> Option<pgrx::AnyNumeric> {
+        let collect = decode_collect(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(collect.amount0))
+    }
+
+    #[pg_extern(name = "try_collect_amount0", immutable, parallel_safe)]
+    fn uni_try_collect_amount0_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let collect = decode_collect(data).ok()?;
+        Some(decode::numeric(collect.amount0))
+    }
+
+    #[pg_extern(name = "collect_amount1", immutable, parallel_safe)]
+    fn uni_collect_amount1(data: &str) -> pgrx::AnyNumeric {
+        let collect = decode_collect(&decode::hex(data).or_report()).or_report();
+        decode::numeric(collect.amount1)
+    }
+
+    #[pg_extern(name = "collect_amount1", immutable, parallel_safe)]
+    fn uni_collect_amount1_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let collect = decode_collect(data).or_report();
+        decode::numeric(collect.amount1)
+    }
+
+    #[pg_extern(name = "try_collect_amount1", immutable, parallel_safe)]
+    fn uni_try_collect_amount1(data: &str) -> Option<pgrx::AnyNumeric> {
+        let collect = decode_collect(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(collect.amount1))
+    }
+
+    #[pg_extern(name = "try_collect_amount1", immutable, parallel_safe)]
+    fn uni_try_collect_amount1_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let collect = decode_collect(data).ok()?;
+        Some(decode::numeric(collect.amount1))
+    }
+
+    #[pg_extern(name = "position_owner", immutable, parallel_safe)]
+    fn uni_position_owner(topics: Array<&str>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "position_owner_raw", immutable, parallel_safe)]
+    fn uni_position_owner_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "try_position_owner", immutable, parallel_safe)]
+    fn uni_try_position_owner(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_position_owner_raw", immutable, parallel_safe)]
+    fn uni_try_position_owner_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "position_tick_lower", immutable, parallel_safe)]
+    fn uni_position_tick_lower(topics: Array<&str>) -> i32 {
+        position_tick(&topics, 2).or_report()
+    }
+
+    #[pg_extern(name = "position_tick_lower_raw", immutable, parallel_safe)]
+    fn uni_position_tick_lower_raw(topics: Array<&[u8]>) -> i32 {
+        position_tick(&topics, 2).or_report()
+    }
+
+    #[pg_extern(name = "try_position_tick_lower", immutable, parallel_safe)]
+    fn uni_try_position_tick_lower(topics: Array<&str>) -> Option<i32> {
+        position_tick(&topics, 2).ok()
+    }
+
+    #[pg_extern(name = "try_position_tick_lower_raw", immutable, parallel_safe)]
+    fn uni_try_position_tick_lower_raw(topics: Array<&[u8]>) -> Option<i32> {
+        position_tick(&topics, 2).ok()
+    }
+
+    #[pg_extern(name = "position_tick_upper", immutable, parallel_safe)]
+    fn uni_position_tick_upper(topics: Array<&str>) -> i32 {
+        position_tick(&topics, 3).or_report()
+    }
+
+    #[pg_extern(name = "position_tick_upper_raw", immutable, parallel_safe)]
+    fn uni_position_tick_upper_raw(topics: Array<&[u8]>) -> i32 {
+        position_tick(&topics, 3).or_report()
+    }
+
+    #[pg_extern(name = "try_position_tick_upper", immutable, parallel_safe)]
+    fn uni_try_position_tick_upper(topics: Array<&str>) -> Option<i32> {
+        position_tick(&topics, 3).ok()
+    }
+
+    #[pg_extern(name = "try_position_tick_upper_raw", immutable, parallel_safe)]
+    fn uni_try_position_tick_upper_raw(topics: Array<&[u8]>) -> Option<i32> {
+        position_tick(&topics, 3).ok()
+    }
+
+    #[pg_extern(name = "amounts_for_liquidity", immutable, parallel_safe)]
+    fn uni_amounts_for_liquidity(
+        sqrt_price_x96: pgrx::AnyNumeric,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: pgrx::AnyNumeric,
+    ) -> Vec<pgrx::AnyNumeric> {
+        let (amount0, amount1) = amounts_for_liquidity(
+            &sqrt_price_x96.to_string(),
+            tick_lower,
+            tick_upper,
+            &liquidity.to_string(),
+        )
+        .or_report();
+
+        vec![decode::numeric(amount0), decode::numeric(amount1)]
+    }
+
+    #[pg_extern(name = "liquidity_for_amounts", immutable, parallel_safe)]
+    fn uni_liquidity_for_amounts(
+        sqrt_price_x96: pgrx::AnyNumeric,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount0: pgrx::AnyNumeric,
+        amount1: pgrx::AnyNumeric,
+    ) -> pgrx::AnyNumeric {
+        let liquidity = liquidity_for_amounts(
+            &sqrt_price_x96.to_string(),
+            tick_lower,
+            tick_upper,
+            &amount0.to_string(),
+            &amount1.to_string(),
+        )
+        .or_report();
+
+        decode::numeric(liquidity)
+    }
+
+    #[pg_extern(name = "swap_tick", immutable, parallel_safe)]
+    fn uni_swap_tick(data: &str) -> i32 {
+        swap_tick(&decode::hex(data).or_report()).or_report()
+    }
+
+    #[pg_extern(name = "swap_tick", immutable, parallel_safe)]
+    fn uni_swap_tick_bytea(data: &[u8]) -> i32 {
+        swap_tick(data).or_report()
+    }
+
+    #[pg_extern(name = "try_swap_tick", immutable, parallel_safe)]
+    fn uni_try_swap_tick(data: &str) -> Option<i32> {
+        swap_tick(&decode::hex(data).ok()?).ok()
+    }
+
+    #[pg_extern(name = "try_swap_tick", immutable, parallel_safe)]
+    fn uni_try_swap_tick_bytea(data: &[u8]) -> Option<i32> {
+        swap_tick(data).ok()
+    }
+
+    #[pg_extern(name = "tick_to_sqrt_price_x96", immutable, parallel_safe)]
+    fn uni_tick_to_sqrt_price_x96(tick: i32) -> pgrx::AnyNumeric {
+        decode::numeric(tick_math::get_sqrt_ratio_at_tick(tick).or_report())
+    }
+
+    #[pg_extern(name = "sqrt_price_x96_to_tick", immutable, parallel_safe)]
+    fn uni_sqrt_price_x96_to_tick(sqrt_price_x96: pgrx::AnyNumeric) -> i32 {
+        let sqrt_price_x96 = u256::parse(&sqrt_price_x96.to_string()).or_report();
+        tick_math::get_tick_at_sqrt_ratio(sqrt_price_x96).or_report()
+    }
+
+    #[pg_extern(name = "tick_to_price", immutable, parallel_safe)]
+    fn uni_tick_to_price(tick: i32, base_decimals: i64, quote_decimals: i64) -> pgrx::AnyNumeric {
+        let sqrt = tick_math::get_sqrt_ratio_at_tick(tick).or_report();
+        let price = sqrt_price_to_price(
+            BigInt::from_bytes_be(Sign::Plus, &sqrt.to_be_bytes::<32>()),
+            base_decimals,
+            quote_decimals,
+        );
+
+        decode::numeric(price)
+    }
+
+    #[pg_extern(name = "price_to_tick", immutable, parallel_safe)]
+    fn uni_price_to_tick(price: pgrx::AnyNumeric, base_decimals: i64, quote_decimals: i64) -> i32 {
+        price_to_tick(&price.to_string(), base_decimals, quote_decimals).or_report()
+    }
+
+    #[pg_extern(name = "sort_tokens", immutable, parallel_safe)]
+    fn uni_sort_tokens(token_a: &str, token_b: &str) -> Vec<String> {
+        let (token0, token1) = sort_tokens(token_a, token_b).or_report();
+        vec![hex::encode(token0), hex::encode(token1)]
+    }
+
+    #[pg_extern(name = "pool_for", immutable, parallel_safe)]
+    fn uni_pool_for(
+        factory: &str,
+        token_a: &str,
+        token_b: &str,
+        fee: i32,
+        init_code_hash: default!(Option<&str>, "NULL"),
+    ) -> String {
+        hex::encode(pool_for(factory, token_a, token_b, fee, init_code_hash).or_report())
     }
 }
 
-#[allow(dead_code)]
-fn decode_swap(data: &[u8]) -> Result<Swap> {
-    let amount_base = BigInt::from_signed_bytes_be(&data[64..96]);
-    let amount_quote = BigInt::from_signed_bytes_be(&data[96..128]);
+/// Init code hash of the V3 pool deployer on mainnet and most of its official deployments.
+const POOL_INIT_CODE_HASH: B256 =
+    b256!("e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54");
+
+/// CREATE2 address of a V3 pool, the salt is keccak256 of the abi encoded (token0, token1, fee).
+fn pool_for(
+    factory: &str,
+    token_a: &str,
+    token_b: &str,
+    fee: i32,
+    init_code_hash: Option<&str>,
+) -> Result<Address> {
+    let factory = decode::h160(factory)?;
+    let init_code_hash = match init_code_hash {
+        Some(hash) => decode::h256(hash)?,
+        None => POOL_INIT_CODE_HASH,
+    };
+
+    let (token0, token1) = sort_tokens(token_a, token_b)?;
+
+    let fee = U24::try_from(fee).map_err(|_| Error::data(format!("invalid fee: {fee}")))?;
+
+    let salt = keccak256(
+        [
+            token0.into_word().as_slice(),
+            token1.into_word().as_slice(),
+            &U256::from(fee).to_be_bytes::<32>(),
+        ]
+        .concat(),
+    );
+
+    Ok(factory.create2(salt, init_code_hash))
+}
+
+pub fn decode_swap(data: &[u8]) -> Result<Swap> {
+    let amount_base = BigInt::from_signed_bytes_be(decode::slice(data, 64, 96)?);
+    let amount_quote = BigInt::from_signed_bytes_be(decode::slice(data, 96, 128)?);
 
     let action = match amount_base.gt(&BigInt::from(0)) {
         true => SwapAction::SELL,
@@ -118,27 +1000,38 @@ fn decode_swap(data: &[u8]) -> Result<Swap> {
     })
 }
 
-fn decode_sync(bytes: &[u8]) -> Result<Sync> {
-    let x96: Ratio<BigInt> = BigRational::from(BigInt::from(10).pow(29));
+/// Virtual reserves of the pool from a Swap event, L / sqrtP and L * sqrtP with sqrtP in Q64.96.
+fn decode_sync(bytes: &[u8], rounding: Rounding) -> Result<Sync> {
+    let q96 = BigInt::from(1) << 96;
 
-    let sqrt = BigInt::from_bytes_be(Sign::Plus, &bytes[128..160]);
-    let sqrt_p = BigRational::from(sqrt.clone()) / &x96;
-
-    let liquidity = BigInt::from_bytes_be(Sign::Plus, &bytes[160..192]);
-    let fixed_liquidity = BigRational::from(liquidity);
+    let sqrt = BigInt::from_bytes_be(Sign::Plus, decode::slice(bytes, 128, 160)?);
+    if sqrt.is_zero() {
+        return Err(Error::data("sqrtPriceX96 is zero"));
+    }
 
-    let base_reserve = &fixed_liquidity / &sqrt_p;
-    let quote_reserve = &fixed_liquidity * &sqrt_p;
+    let liquidity = swap_liquidity(bytes)?;
 
     Ok(Sync {
-        base_reserve: base_reserve.to_integer().to_bigint().unwrap(),
-        quote_reserve: quote_reserve.to_integer().to_bigint().unwrap(),
+        base_reserve: rounding.div(&liquidity * &q96, &sqrt),
+        quote_reserve: rounding.div(&liquidity * &sqrt, &q96),
     })
 }
 
-fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> BigDecimal {
-    let sqrt = BigInt::from_bytes_be(Sign::Plus, &bytes[128..160]);
+/// Active liquidity of the pool after a swap.
+fn swap_liquidity(bytes: &[u8]) -> Result<BigInt> {
+    Ok(BigInt::from_bytes_be(
+        Sign::Plus,
+        decode::slice(bytes, 160, 192)?,
+    ))
+}
+
+fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> Result<BigDecimal> {
+    let sqrt = BigInt::from_bytes_be(Sign::Plus, decode::slice(bytes, 128, 160)?);
+    Ok(sqrt_price_to_price(sqrt, base_decimals, quote_decimals))
+}
 
+/// Price of the base token in quote token units from a sqrtPriceX96, rounded to `quote_decimals`.
+pub fn sqrt_price_to_price(sqrt: BigInt, base_decimals: i64, quote_decimals: i64) -> BigDecimal {
     let p2 = BigDecimal::new(sqrt.pow(2), quote_decimals);
     let exp = BigDecimal::new(BigInt::from(2).pow(192), quote_decimals);
 
@@ -157,6 +1050,174 @@ fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> BigDecim
     price_ratio.round(quote_decimals)
 }
 
+/// Greatest tick whose `getSqrtRatioAtTick` is at or below the sqrtPriceX96 of `price` rounded up,
+/// so an exact tick price maps to that tick. A price within rounding below a tick boundary maps to
+/// the boundary tick too, whose `tick_to_price` is then slightly above `price`.
+fn price_to_tick(price: &str, base_decimals: i64, quote_decimals: i64) -> Result<i32> {
+    let price = BigDecimal::from_str(price).map_err(|_| Error::invalid_text("numeric", price))?;
+    if price <= BigDecimal::zero() {
+        return Err(Error::data(format!("price must be positive: {price}")));
+    }
+
+    // price * 2^192 / 10^(base_decimals - quote_decimals) is sqrtPriceX96 squared
+    let ratio = &price * BigDecimal::new(BigInt::from(2).pow(192), base_decimals - quote_decimals);
+
+    // Both steps round up, getSqrtRatioAtTick rounds up too so an exact tick price maps to that tick
+    let (mut squared, _) = ratio.with_scale(0).into_bigint_and_exponent();
+    if BigDecimal::from(squared.clone()) < ratio {
+        squared += 1;
+    }
+
+    let mut sqrt = squared.sqrt();
+    if &sqrt * &sqrt < squared {
+        sqrt += 1;
+    }
+
+    let (_, sqrt) = sqrt.to_bytes_be();
+    if sqrt.len() > 32 {
+        return Err(Error::data(format!("price out of range: {price}")));
+    }
+
+    tick_math::get_tick_at_sqrt_ratio(U256::from_be_slice(&sqrt))
+}
+
+/// Current tick after a swap, the int24 in the last word of the Swap event data.
+fn swap_tick(data: &[u8]) -> Result<i32> {
+    tick(decode::slice(data, 192, 224)?)
+}
+
+/// Sign extended int24 tick stored in a 32 byte word.
+pub fn tick(word: &[u8]) -> Result<i32> {
+    let tick = BigInt::from_signed_bytes_be(word);
+
+    i32::try_from(&tick)
+        .ok()
+        .filter(|tick| (MIN_TICK..=MAX_TICK).contains(tick))
+        .ok_or_else(|| Error::data(format!("tick out of range: {tick}")))
+}
+
+fn decode_mint(data: &[u8]) -> Result<PositionChange> {
+    // sender is the only non indexed address and comes first
+    position_change(data, 96)
+}
+
+fn decode_burn(data: &[u8]) -> Result<PositionChange> {
+    position_change(data, 64)
+}
+
+fn position_change(data: &[u8], offset: usize) -> Result<PositionChange> {
+    Ok(PositionChange {
+        liquidity: BigInt::from_bytes_be(Sign::Plus, decode::slice(data, offset, offset + 32)?),
+        amount0: BigInt::from_bytes_be(Sign::Plus, decode::slice(data, offset + 32, offset + 64)?),
+        amount1: BigInt::from_bytes_be(Sign::Plus, decode::slice(data, offset + 64, offset + 96)?),
+    })
+}
+
+fn decode_collect(data: &[u8]) -> Result<Collect> {
+    Ok(Collect {
+        amount0: BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 96, 128)?),
+        amount1: BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 128, 160)?),
+    })
+}
+
+/// Lower (2) or upper (3) tick of the position in a Mint, Burn or Collect event.
+fn position_tick(topics: &impl Topics, index: usize) -> Result<i32> {
+    tick(topics.topic(index)?.as_slice())
+}
+
+/// Every field of a Swap event in one row, amounts are absolute like `swap_base_amount`.
+fn swap_row(data: &[u8]) -> Result<decode::Row> {
+    let swap = decode_swap(data)?;
+    let sqrt = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 128, 160)?);
+
+    Ok(decode::row("Uniswap.swap")
+        .with("action", swap.action as i32)
+        .with("base_amount", decode::numeric(swap.base_amount.abs()))
+        .with("quote_amount", decode::numeric(swap.quote_amount.abs()))
+        .with("sqrt_price_x96", decode::numeric(sqrt))
+        .with("liquidity", decode::numeric(swap_liquidity(data)?))
+        .with("tick", swap_tick(data)?))
+}
+
+fn sync_row(sync: Sync) -> decode::Row {
+    decode::row("Uniswap.sync")
+        .with("base_reserve", decode::numeric(sync.base_reserve))
+        .with("quote_reserve", decode::numeric(sync.quote_reserve))
+}
+
+fn position_row(topics: &impl Topics, change: PositionChange) -> Result<decode::Row> {
+    Ok(decode::row("Uniswap.position")
+        .with("owner", hex::encode(topics.address(1)?))
+        .with("tick_lower", position_tick(topics, 2)?)
+        .with("tick_upper", position_tick(topics, 3)?)
+        .with("liquidity", decode::numeric(change.liquidity))
+        .with("amount0", decode::numeric(change.amount0))
+        .with("amount1", decode::numeric(change.amount1)))
+}
+
+fn collect_row(topics: &impl Topics, collect: Collect) -> Result<decode::Row> {
+    Ok(decode::row("Uniswap.collect")
+        .with("owner", hex::encode(topics.address(1)?))
+        .with("tick_lower", position_tick(topics, 2)?)
+        .with("tick_upper", position_tick(topics, 3)?)
+        .with("amount0", decode::numeric(collect.amount0))
+        .with("amount1", decode::numeric(collect.amount1)))
+}
+
+fn tick_range(tick_lower: i32, tick_upper: i32) -> Result<(U256, U256)> {
+    if tick_lower >= tick_upper {
+        return Err(Error::data(format!(
+            "invalid tick range: {tick_lower} must be less than {tick_upper}"
+        )));
+    }
+
+    Ok((
+        tick_math::get_sqrt_ratio_at_tick(tick_lower)?,
+        tick_math::get_sqrt_ratio_at_tick(tick_upper)?,
+    ))
+}
+
+fn parse_liquidity(value: &str) -> Result<u128> {
+    u128::try_from(u256::parse(value)?)
+        .map_err(|_| Error::data(format!("liquidity out of range: {value}")))
+}
+
+/// Token0 and token1 amounts of a position, same as `LiquidityAmounts.getAmountsForLiquidity`.
+fn amounts_for_liquidity(
+    sqrt_price_x96: &str,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: &str,
+) -> Result<(U256, U256)> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = tick_range(tick_lower, tick_upper)?;
+
+    liquidity_amounts::get_amounts_for_liquidity(
+        u256::parse(sqrt_price_x96)?,
+        sqrt_ratio_a,
+        sqrt_ratio_b,
+        parse_liquidity(liquidity)?,
+    )
+}
+
+/// Liquidity minted for the given amounts, same as `LiquidityAmounts.getLiquidityForAmounts`.
+fn liquidity_for_amounts(
+    sqrt_price_x96: &str,
+    tick_lower: i32,
+    tick_upper: i32,
+    amount0: &str,
+    amount1: &str,
+) -> Result<u128> {
+    let (sqrt_ratio_a, sqrt_ratio_b) = tick_range(tick_lower, tick_upper)?;
+
+    liquidity_amounts::get_liquidity_for_amounts(
+        u256::parse(sqrt_price_x96)?,
+        sqrt_ratio_a,
+        sqrt_ratio_b,
+        u256::parse(amount0)?,
+        u256::parse(amount1)?,
+    )
+}
+
 #[cfg(any(test, feature = "pg_test"))]
 #[pg_schema]
 mod tests {
@@ -205,6 +1266,38 @@ mod tests {
         Ok(())
     }
 
+    #[pg_test]
+    fn uni_test_swap_addresses() -> Result<()> {
+        let topics = "{0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67,0x000000000000000000000000e592427a0aece92de3edee1f18e0157c05861564,0x0000000000000000000000001111111111111111111111111111111111111111}";
+
+        let sender = Spi::get_one_with_args::<String>(
+            "SELECT Uniswap.swap_sender($1::text[]);",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        let recipient = Spi::get_one_with_args::<String>(
+            "SELECT Uniswap.swap_recipient($1::text[]);",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        assert_eq!(
+            sender,
+            Some(String::from("e592427a0aece92de3edee1f18e0157c05861564"))
+        );
+        assert_eq!(
+            recipient,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+
+        let missing = Spi::get_one::<String>(
+            "SELECT Uniswap.try_swap_recipient('{0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67}');",
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
     #[pg_test]
     fn uni_test_sync() -> Result<()> {
         let data = "0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000058418f10da628473affffffffffffffffffffffffffffffffffffffffffffffffffffd722236f32722e0000000000000000000000000000000000000000002bc4f31f2528f3970405f300000000000000000000000000000000000000000000ac695d7b1db89e7cd0ddfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffdc4c4";
@@ -229,12 +1322,12 @@ mod tests {
 
         assert_eq!(
             reserve_base,
-            Some(pgrx::AnyNumeric::from_str("1538709118419255752482357599")?)
+            Some(pgrx::AnyNumeric::from_str("1219090960963012042101286662")?)
         );
 
         assert_eq!(
             reserve_quote,
-            Some(pgrx::AnyNumeric::from_str("430819869816330615202")?)
+            Some(pgrx::AnyNumeric::from_str("543771123984814448197")?)
         );
 
         assert_eq!(
@@ -245,6 +1338,48 @@ mod tests {
         Ok(())
     }
 
+    #[pg_test]
+    fn uni_test_sync_rounding() -> Result<()> {
+        let data = "0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000058418f10da628473affffffffffffffffffffffffffffffffffffffffffffffffffffd722236f32722e0000000000000000000000000000000000000000002bc4f31f2528f3970405f300000000000000000000000000000000000000000000ac695d7b1db89e7cd0ddfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffdc4c4";
+
+        let base_up = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.sync_base_reserve($1, 'up');",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        let quote_nearest = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.sync_quote_reserve($1, 'nearest');",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        let liquidity = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.swap_liquidity($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            base_up,
+            Some(pgrx::AnyNumeric::from_str("1219090960963012042101286663")?)
+        );
+
+        assert_eq!(
+            quote_nearest,
+            Some(pgrx::AnyNumeric::from_str("543771123984814448198")?)
+        );
+
+        assert_eq!(
+            liquidity,
+            Some(pgrx::AnyNumeric::from_str("814190679191664912552157")?)
+        );
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid input syntax for rounding: \"ceil\"")]
+    fn uni_test_sync_invalid_rounding() {
+        Spi::run("SELECT Uniswap.sync_base_reserve('00', 'ceil');").unwrap();
+    }
+
     #[pg_test]
     fn uni_test_sync_diff_decimals() -> Result<()> {
         let data = "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000ffffffffffffffffffffffffffffffffffffffffffffffffa99a52af25fb226800000000000000000000000000000000000000000000000000000002830ac9a200000000000000000000000000000000000000000002ba3e80dffbea705b06590000000000000000000000000000000000000000000000008220d5a03bc02470fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcebea";
@@ -259,4 +1394,305 @@ mod tests {
 
         Ok(())
     }
+
+    #[pg_test]
+    fn uni_test_tick_math() -> Result<()> {
+        let min =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT Uniswap.tick_to_sqrt_price_x96(-887272);")?;
+        let max =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT Uniswap.tick_to_sqrt_price_x96(887272);")?;
+        let zero = Spi::get_one::<pgrx::AnyNumeric>("SELECT Uniswap.tick_to_sqrt_price_x96(0);")?;
+
+        assert_eq!(min, Some(pgrx::AnyNumeric::from_str("4295128739")?));
+        assert_eq!(
+            max,
+            Some(pgrx::AnyNumeric::from_str(
+                "1461446703485210103287273052203988822378723970342"
+            )?)
+        );
+        assert_eq!(
+            zero,
+            Some(pgrx::AnyNumeric::from_str("79228162514264337593543950336")?)
+        );
+
+        let min_tick = Spi::get_one::<i32>("SELECT Uniswap.sqrt_price_x96_to_tick(4295128739);")?;
+        let max_tick = Spi::get_one::<i32>(
+            "SELECT Uniswap.sqrt_price_x96_to_tick(1461446703485210103287273052203988822378723970341);",
+        )?;
+
+        assert_eq!(min_tick, Some(-887272));
+        assert_eq!(max_tick, Some(887271));
+
+        let tick = Spi::get_one::<i32>(
+            "SELECT Uniswap.sqrt_price_x96_to_tick(Uniswap.tick_to_sqrt_price_x96(-201750) - 1);",
+        )?;
+
+        assert_eq!(tick, Some(-201751));
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn uni_test_tick_price() -> Result<()> {
+        let data = "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000ffffffffffffffffffffffffffffffffffffffffffffffffa99a52af25fb226800000000000000000000000000000000000000000000000000000002830ac9a200000000000000000000000000000000000000000002ba3e80dffbea705b06590000000000000000000000000000000000000000000000008220d5a03bc02470fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcebea";
+
+        let tick = Spi::get_one_with_args::<i32>(
+            "SELECT Uniswap.swap_tick($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(tick, Some(-201750));
+
+        let price =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT Uniswap.tick_to_price(-201750, 18, 6);")?;
+
+        assert_eq!(price, Some(pgrx::AnyNumeric::from_str("1731.996033")?));
+
+        // The pool price sits between ticks, its tick is the one below
+        let tick = Spi::get_one::<i32>("SELECT Uniswap.price_to_tick(1732.107430, 18, 6);")?;
+
+        assert_eq!(tick, Some(-201750));
+
+        let exact = Spi::get_one::<i32>("SELECT Uniswap.price_to_tick(1.0001, 18, 18);")?;
+
+        assert_eq!(exact, Some(1));
+
+        // One unit below the boundary at 18 decimals is in the tick below, within the rounding of
+        // sqrtPriceX96 it is still the boundary tick
+        let (below, rounded) = Spi::get_two::<i32, i32>(
+            "SELECT Uniswap.price_to_tick(1.000099999999999999, 18, 18), Uniswap.price_to_tick(1.000099999999999999999999999999, 18, 18);",
+        )?;
+
+        assert_eq!(below, Some(0));
+        assert_eq!(rounded, Some(1));
+
+        let short = Spi::get_one::<i32>("SELECT Uniswap.try_swap_tick('0020');")?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "tick out of range: 887273")]
+    fn uni_test_tick_out_of_range() {
+        Spi::run("SELECT Uniswap.tick_to_sqrt_price_x96(887273);").unwrap();
+    }
+
+    #[pg_test]
+    fn uni_test_mint() -> Result<()> {
+        let topics = "{0x7a53080ba414158be7ec69b987b5fb7d07dee101fe85488f0853ae16239d0bde,0x000000000000000000000000c36442b4a4522e871399cd717abdd847ab11fe88,0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffc4,0x000000000000000000000000000000000000000000000000000000000000003c}";
+        let data = "00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000080000000000000000000000000c36442b4a4522e871399cd717abdd847ab11fe880000000000000000000000000000000000000000000000000de0b6b3a7640000000000000000000000000000000000000000000000000000000aa4426c1f8e7c000000000000000000000000000000000000000000000000000aa4426c1f8e7c";
+
+        let owner = Spi::get_one_with_args::<String>(
+            "SELECT Uniswap.position_owner($1::text[]);",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        assert_eq!(
+            owner,
+            Some(String::from("c36442b4a4522e871399cd717abdd847ab11fe88"))
+        );
+
+        let tick_lower = Spi::get_one_with_args::<i32>(
+            "SELECT Uniswap.position_tick_lower($1::text[]);",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        let tick_upper = Spi::get_one_with_args::<i32>(
+            "SELECT Uniswap.position_tick_upper($1::text[]);",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        assert_eq!(tick_lower, Some(-60));
+        assert_eq!(tick_upper, Some(60));
+
+        let liquidity = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.mint_liquidity($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        let amount0 = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.mint_amount0($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            liquidity,
+            Some(pgrx::AnyNumeric::from_str("1000000000000000000")?)
+        );
+        assert_eq!(
+            amount0,
+            Some(pgrx::AnyNumeric::from_str("2995354955910780")?)
+        );
+
+        // Burn has no sender so the same payload is read one word earlier
+        let burned = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.burn_amount1($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            burned,
+            Some(pgrx::AnyNumeric::from_str("2995354955910780")?)
+        );
+
+        let short =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT Uniswap.try_collect_amount1('0020');")?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn uni_test_decode_swap() -> Result<()> {
+        let data = "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000ffffffffffffffffffffffffffffffffffffffffffffffffa99a52af25fb226800000000000000000000000000000000000000000000000000000002830ac9a200000000000000000000000000000000000000000002ba3e80dffbea705b06590000000000000000000000000000000000000000000000008220d5a03bc02470fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcebea";
+
+        let (action, liquidity, tick) = Spi::get_three_with_args::<i32, pgrx::AnyNumeric, i32>(
+            "SELECT action, liquidity, tick FROM Uniswap.decode_swap($1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(action, Some(SwapAction::BUY as i32));
+        assert_eq!(
+            liquidity,
+            Some(pgrx::AnyNumeric::from_str("9376729308359304304")?)
+        );
+        assert_eq!(tick, Some(-201750));
+
+        let (base_reserve, quote_reserve) = Spi::get_two::<pgrx::AnyNumeric, pgrx::AnyNumeric>(
+            "SELECT base_reserve, quote_reserve FROM Uniswap.decode_sync('0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000058418f10da628473affffffffffffffffffffffffffffffffffffffffffffffffffffd722236f32722e0000000000000000000000000000000000000000002bc4f31f2528f3970405f300000000000000000000000000000000000000000000ac695d7b1db89e7cd0ddfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffdc4c4', 'up');",
+        )?;
+
+        assert_eq!(
+            base_reserve,
+            Some(pgrx::AnyNumeric::from_str("1219090960963012042101286663")?)
+        );
+        assert_eq!(
+            quote_reserve,
+            Some(pgrx::AnyNumeric::from_str("543771123984814448198")?)
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn uni_test_decode_mint() -> Result<()> {
+        let topics = "{0x7a53080ba414158be7ec69b987b5fb7d07dee101fe85488f0853ae16239d0bde,0x000000000000000000000000c36442b4a4522e871399cd717abdd847ab11fe88,0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffc4,0x000000000000000000000000000000000000000000000000000000000000003c}";
+        let data = "00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000080000000000000000000000000c36442b4a4522e871399cd717abdd847ab11fe880000000000000000000000000000000000000000000000000de0b6b3a7640000000000000000000000000000000000000000000000000000000aa4426c1f8e7c000000000000000000000000000000000000000000000000000aa4426c1f8e7c";
+
+        let (owner, tick_lower, tick_upper) = Spi::get_three_with_args::<String, i32, i32>(
+            "SELECT owner, tick_lower, tick_upper FROM Uniswap.decode_mint($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            owner,
+            Some(String::from("c36442b4a4522e871399cd717abdd847ab11fe88"))
+        );
+        assert_eq!(tick_lower, Some(-60));
+        assert_eq!(tick_upper, Some(60));
+
+        let (liquidity, amount0, amount1) =
+            Spi::get_three_with_args::<pgrx::AnyNumeric, pgrx::AnyNumeric, pgrx::AnyNumeric>(
+                "SELECT liquidity, amount0, amount1 FROM Uniswap.decode_mint($1::text[], $2);",
+                &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+            )?;
+
+        assert_eq!(
+            liquidity,
+            Some(pgrx::AnyNumeric::from_str("1000000000000000000")?)
+        );
+        assert_eq!(
+            amount0,
+            Some(pgrx::AnyNumeric::from_str("2995354955910780")?)
+        );
+        assert_eq!(
+            amount1,
+            Some(pgrx::AnyNumeric::from_str("2995354955910780")?)
+        );
+
+        let missing = Spi::get_one_with_args::<String>(
+            "SELECT owner FROM Uniswap.try_decode_collect('{}'::text[], $1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn uni_test_liquidity_amounts() -> Result<()> {
+        let amounts = Spi::get_one::<Vec<pgrx::AnyNumeric>>(
+            "SELECT Uniswap.amounts_for_liquidity(79228162514264337593543950336, -60, 60, 1000000000000000000);",
+        )?;
+
+        assert_eq!(
+            amounts,
+            Some(vec![
+                pgrx::AnyNumeric::from_str("2995354955910780")?,
+                pgrx::AnyNumeric::from_str("2995354955910780")?
+            ])
+        );
+
+        // Price below the range, the position is all token0
+        let below = Spi::get_one::<Vec<pgrx::AnyNumeric>>(
+            "SELECT Uniswap.amounts_for_liquidity(79228162514264337593543950336, 60, 120, 1000000000000000000);",
+        )?;
+
+        assert_eq!(
+            below,
+            Some(vec![
+                pgrx::AnyNumeric::from_str("2986382804598881")?,
+                pgrx::AnyNumeric::from_str("0")?
+            ])
+        );
+
+        let liquidity = Spi::get_one::<pgrx::AnyNumeric>(
+            "SELECT Uniswap.liquidity_for_amounts(79228162514264337593543950336, -60, 60, 2995354955910781, 2995354955910781);",
+        )?;
+
+        assert_eq!(
+            liquidity,
+            Some(pgrx::AnyNumeric::from_str("1000000000000000020")?)
+        );
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid tick range: 60 must be less than -60")]
+    fn uni_test_liquidity_amounts_invalid_range() {
+        Spi::run(
+            "SELECT Uniswap.amounts_for_liquidity(79228162514264337593543950336, 60, -60, 1);",
+        )
+        .unwrap();
+    }
+
+    #[pg_test]
+    fn uni_test_pool_for() -> Result<()> {
+        let pool = Spi::get_one::<String>(
+            "SELECT Uniswap.pool_for(
+                '0x1F98431c8aD98523631AE4a59f267346ea31F984',
+                '0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2',
+                '0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48',
+                500
+            );",
+        )?;
+
+        assert_eq!(
+            pool,
+            Some(String::from("88e6a0c2ddd26feeb64f039a2c41296fcb3f5640"))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test(error = "identical token addresses: 0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")]
+    fn uni_test_pool_for_identical_tokens() {
+        Spi::run(
+            "SELECT Uniswap.pool_for('0x1F98431c8aD98523631AE4a59f267346ea31F984', '0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48', '0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48', 500);",
+        )
+        .unwrap();
+    }
 }
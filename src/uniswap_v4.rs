@@ -0,0 +1,709 @@
+use pgrx::prelude::*;
+
+use num::{bigint::Sign, BigInt, Signed, Zero};
+
+use alloy::core::hex;
+use alloy::primitives::{aliases::U24, keccak256, Address, B256, I256, U256};
+
+use crate::decode::{self, Error, Result, Topics, With};
+use crate::h160::sort_tokens;
+use crate::uniswap::{self, SwapAction};
+
+/// Swap of the V4 PoolManager, amounts are from the swapper's point of view so a negative
+/// amount is what went into the pool.
+pub struct Swap {
+    pub action: SwapAction,
+    pub amount0: BigInt,
+    pub amount1: BigInt,
+    pub sqrt_price_x96: BigInt,
+    pub liquidity: BigInt,
+    pub tick: i32,
+    pub fee: i32,
+}
+
+pub struct ModifyLiquidity {
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity_delta: BigInt,
+    pub salt: B256,
+}
+
+pub struct Initialize {
+    pub fee: i32,
+    pub tick_spacing: i32,
+    pub hooks: Address,
+    pub sqrt_price_x96: BigInt,
+    pub tick: i32,
+}
+
+#[pg_schema]
+#[allow(non_snake_case)]
+mod UniswapV4 {
+    use pgrx::prelude::*;
+
+    use alloy::core::hex;
+
+    use super::{
+        decode_initialize, decode_modify_liquidity, decode_swap, event_pool_id, initialize_row,
+        modify_liquidity_row, pool_id, swap_row,
+    };
+    use crate::decode::{self, OrReport};
+    use crate::uniswap::sqrt_price_to_price;
+
+    extension_sql!(
+        r#"
+CREATE TYPE UniswapV4.swap AS (
+    pool_id text,
+    sender text,
+    action integer,
+    base_amount numeric,
+    quote_amount numeric,
+    sqrt_price_x96 numeric,
+    liquidity numeric,
+    tick integer,
+    fee integer
+);
+
+CREATE TYPE UniswapV4.modify_liquidity AS (
+    pool_id text,
+    sender text,
+    tick_lower integer,
+    tick_upper integer,
+    liquidity_delta numeric,
+    salt text
+);
+
+CREATE TYPE UniswapV4.initialize AS (
+    pool_id text,
+    currency0 text,
+    currency1 text,
+    fee integer,
+    tick_spacing integer,
+    hooks text,
+    sqrt_price_x96 numeric,
+    tick integer
+);
+"#,
+        name = "uniswap_v4_types",
+    );
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["uniswap_v4_types"])]
+    fn univ4_decode_swap(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "UniswapV4.swap") {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        swap_row(&topics, swap).or_report()
+    }
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["uniswap_v4_types"])]
+    fn univ4_decode_swap_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "UniswapV4.swap") {
+        let swap = decode_swap(data).or_report();
+        swap_row(&topics, swap).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_try_decode_swap(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "UniswapV4.swap")> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        swap_row(&topics, swap).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_try_decode_swap_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "UniswapV4.swap")> {
+        let swap = decode_swap(data).ok()?;
+        swap_row(&topics, swap).ok()
+    }
+
+    #[pg_extern(
+        name = "decode_modify_liquidity",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_decode_modify_liquidity(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "UniswapV4.modify_liquidity") {
+        let modify = decode_modify_liquidity(&decode::hex(data).or_report()).or_report();
+        modify_liquidity_row(&topics, modify).or_report()
+    }
+
+    #[pg_extern(
+        name = "decode_modify_liquidity",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_decode_modify_liquidity_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "UniswapV4.modify_liquidity") {
+        let modify = decode_modify_liquidity(data).or_report();
+        modify_liquidity_row(&topics, modify).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_modify_liquidity",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_try_decode_modify_liquidity(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "UniswapV4.modify_liquidity")> {
+        let modify = decode_modify_liquidity(&decode::hex(data).ok()?).ok()?;
+        modify_liquidity_row(&topics, modify).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_modify_liquidity",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_try_decode_modify_liquidity_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "UniswapV4.modify_liquidity")> {
+        let modify = decode_modify_liquidity(data).ok()?;
+        modify_liquidity_row(&topics, modify).ok()
+    }
+
+    #[pg_extern(
+        name = "decode_initialize",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_decode_initialize(
+        topics: Array<&str>,
+        data: &str,
+    ) -> pgrx::composite_type!('static, "UniswapV4.initialize") {
+        let initialize = decode_initialize(&decode::hex(data).or_report()).or_report();
+        initialize_row(&topics, initialize).or_report()
+    }
+
+    #[pg_extern(
+        name = "decode_initialize",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_decode_initialize_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> pgrx::composite_type!('static, "UniswapV4.initialize") {
+        let initialize = decode_initialize(data).or_report();
+        initialize_row(&topics, initialize).or_report()
+    }
+
+    #[pg_extern(
+        name = "try_decode_initialize",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_try_decode_initialize(
+        topics: Array<&str>,
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "UniswapV4.initialize")> {
+        let initialize = decode_initialize(&decode::hex(data).ok()?).ok()?;
+        initialize_row(&topics, initialize).ok()
+    }
+
+    #[pg_extern(
+        name = "try_decode_initialize",
+        immutable,
+        parallel_safe,
+        requires = ["uniswap_v4_types"]
+    )]
+    fn univ4_try_decode_initialize_bytea(
+        topics: Array<&[u8]>,
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "UniswapV4.initialize")> {
+        let initialize = decode_initialize(data).ok()?;
+        initialize_row(&topics, initialize).ok()
+    }
+
+    #[pg_extern(name = "pool_id", immutable, parallel_safe)]
+    fn univ4_pool_id(
+        currency0: &str,
+        currency1: &str,
+        fee: i32,
+        tick_spacing: i32,
+        hooks: default!(&str, "'0x0000000000000000000000000000000000000000'"),
+    ) -> String {
+        hex::encode(pool_id(currency0, currency1, fee, tick_spacing, hooks).or_report())
+    }
+
+    #[pg_extern(name = "pool_id", immutable, parallel_safe)]
+    fn univ4_event_pool_id(topics: Array<&str>) -> String {
+        hex::encode(event_pool_id(&topics).or_report())
+    }
+
+    #[pg_extern(name = "pool_id_raw", immutable, parallel_safe)]
+    fn univ4_event_pool_id_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(event_pool_id(&topics).or_report())
+    }
+
+    #[pg_extern(name = "try_pool_id", immutable, parallel_safe)]
+    fn univ4_try_event_pool_id(topics: Array<&str>) -> Option<String> {
+        event_pool_id(&topics).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_pool_id_raw", immutable, parallel_safe)]
+    fn univ4_try_event_pool_id_raw(topics: Array<&[u8]>) -> Option<String> {
+        event_pool_id(&topics).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "swap_price", immutable, parallel_safe)]
+    fn univ4_swap_price(data: &str, base_decimals: i64, quote_decimals: i64) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(sqrt_price_to_price(
+            swap.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        ))
+    }
+
+    #[pg_extern(name = "swap_price", immutable, parallel_safe)]
+    fn univ4_swap_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(sqrt_price_to_price(
+            swap.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        ))
+    }
+
+    #[pg_extern(name = "try_swap_price", immutable, parallel_safe)]
+    fn univ4_try_swap_price(
+        data: &str,
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(sqrt_price_to_price(
+            swap.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        )))
+    }
+
+    #[pg_extern(name = "try_swap_price", immutable, parallel_safe)]
+    fn univ4_try_swap_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(sqrt_price_to_price(
+            swap.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        )))
+    }
+
+    #[pg_extern(name = "initialize_price", immutable, parallel_safe)]
+    fn univ4_initialize_price(
+        data: &str,
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> pgrx::AnyNumeric {
+        let initialize = decode_initialize(&decode::hex(data).or_report()).or_report();
+        decode::numeric(sqrt_price_to_price(
+            initialize.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        ))
+    }
+
+    #[pg_extern(name = "initialize_price", immutable, parallel_safe)]
+    fn univ4_initialize_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> pgrx::AnyNumeric {
+        let initialize = decode_initialize(data).or_report();
+        decode::numeric(sqrt_price_to_price(
+            initialize.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        ))
+    }
+
+    #[pg_extern(name = "try_initialize_price", immutable, parallel_safe)]
+    fn univ4_try_initialize_price(
+        data: &str,
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let initialize = decode_initialize(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(sqrt_price_to_price(
+            initialize.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        )))
+    }
+
+    #[pg_extern(name = "try_initialize_price", immutable, parallel_safe)]
+    fn univ4_try_initialize_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let initialize = decode_initialize(data).ok()?;
+        Some(decode::numeric(sqrt_price_to_price(
+            initialize.sqrt_price_x96,
+            base_decimals,
+            quote_decimals,
+        )))
+    }
+}
+
+/// PoolId of a PoolKey, keccak256 of the abi encoded (currency0, currency1, fee, tickSpacing,
+/// hooks). Currencies are sorted first, the zero address is native ETH.
+fn pool_id(
+    currency_a: &str,
+    currency_b: &str,
+    fee: i32,
+    tick_spacing: i32,
+    hooks: &str,
+) -> Result<B256> {
+    let (currency0, currency1) = sort_tokens(currency_a, currency_b)?;
+    let hooks = decode::h160(hooks)?;
+
+    let fee = U24::try_from(fee).map_err(|_| Error::data(format!("invalid fee: {fee}")))?;
+    let tick_spacing = I256::try_from(tick_spacing).expect("i32 to fit in i256");
+
+    Ok(keccak256(
+        [
+            currency0.into_word().as_slice(),
+            currency1.into_word().as_slice(),
+            &U256::from(fee).to_be_bytes::<32>(),
+            &tick_spacing.to_be_bytes::<32>(),
+            hooks.into_word().as_slice(),
+        ]
+        .concat(),
+    ))
+}
+
+/// PoolId in the first indexed topic of every PoolManager event.
+fn event_pool_id(topics: &impl Topics) -> Result<B256> {
+    topics.topic(1)
+}
+
+/// Word `index` of the event payload, which starts after the 64 byte header like for V3.
+fn word(data: &[u8], index: usize) -> Result<&[u8]> {
+    decode::slice(data, 64 + index * 32, 64 + (index + 1) * 32)
+}
+
+fn signed(data: &[u8], index: usize) -> Result<BigInt> {
+    Ok(BigInt::from_signed_bytes_be(word(data, index)?))
+}
+
+fn unsigned(data: &[u8], index: usize) -> Result<BigInt> {
+    Ok(BigInt::from_bytes_be(Sign::Plus, word(data, index)?))
+}
+
+fn uint24(data: &[u8], index: usize, field: &str) -> Result<i32> {
+    let value = unsigned(data, index)?;
+
+    i32::try_from(&value)
+        .ok()
+        .filter(|value| *value < 1 << 24)
+        .ok_or_else(|| Error::data(format!("{field} out of range: {value}")))
+}
+
+fn int24(data: &[u8], index: usize, field: &str) -> Result<i32> {
+    let value = signed(data, index)?;
+
+    i32::try_from(&value)
+        .ok()
+        .filter(|value| (-(1 << 23)..1 << 23).contains(value))
+        .ok_or_else(|| Error::data(format!("{field} out of range: {value}")))
+}
+
+pub fn decode_swap(data: &[u8]) -> Result<Swap> {
+    let amount0 = signed(data, 0)?;
+    let amount1 = signed(data, 1)?;
+
+    // The swapper pays a negative amount into the pool, paying token0 sells the base token
+    let action = match amount0.is_negative() {
+        true => SwapAction::SELL,
+        false => SwapAction::BUY,
+    };
+
+    Ok(Swap {
+        action,
+        amount0,
+        amount1,
+        sqrt_price_x96: unsigned(data, 2)?,
+        liquidity: unsigned(data, 3)?,
+        tick: uniswap::tick(word(data, 4)?)?,
+        fee: uint24(data, 5, "fee")?,
+    })
+}
+
+fn decode_modify_liquidity(data: &[u8]) -> Result<ModifyLiquidity> {
+    Ok(ModifyLiquidity {
+        tick_lower: uniswap::tick(word(data, 0)?)?,
+        tick_upper: uniswap::tick(word(data, 1)?)?,
+        liquidity_delta: signed(data, 2)?,
+        salt: B256::from_slice(word(data, 3)?),
+    })
+}
+
+fn decode_initialize(data: &[u8]) -> Result<Initialize> {
+    let sqrt_price_x96 = unsigned(data, 3)?;
+    if sqrt_price_x96.is_zero() {
+        return Err(Error::data("sqrtPriceX96 is zero"));
+    }
+
+    Ok(Initialize {
+        fee: uint24(data, 0, "fee")?,
+        tick_spacing: int24(data, 1, "tick spacing")?,
+        hooks: Address::from_word(B256::from_slice(word(data, 2)?)),
+        sqrt_price_x96,
+        tick: uniswap::tick(word(data, 4)?)?,
+    })
+}
+
+fn swap_row(topics: &impl Topics, swap: Swap) -> Result<decode::Row> {
+    Ok(decode::row("UniswapV4.swap")
+        .with("pool_id", hex::encode(event_pool_id(topics)?))
+        .with("sender", hex::encode(topics.address(2)?))
+        .with("action", swap.action as i32)
+        .with("base_amount", decode::numeric(swap.amount0.abs()))
+        .with("quote_amount", decode::numeric(swap.amount1.abs()))
+        .with("sqrt_price_x96", decode::numeric(swap.sqrt_price_x96))
+        .with("liquidity", decode::numeric(swap.liquidity))
+        .with("tick", swap.tick)
+        .with("fee", swap.fee))
+}
+
+fn modify_liquidity_row(topics: &impl Topics, modify: ModifyLiquidity) -> Result<decode::Row> {
+    Ok(decode::row("UniswapV4.modify_liquidity")
+        .with("pool_id", hex::encode(event_pool_id(topics)?))
+        .with("sender", hex::encode(topics.address(2)?))
+        .with("tick_lower", modify.tick_lower)
+        .with("tick_upper", modify.tick_upper)
+        .with("liquidity_delta", decode::numeric(modify.liquidity_delta))
+        .with("salt", hex::encode(modify.salt)))
+}
+
+fn initialize_row(topics: &impl Topics, initialize: Initialize) -> Result<decode::Row> {
+    Ok(decode::row("UniswapV4.initialize")
+        .with("pool_id", hex::encode(event_pool_id(topics)?))
+        .with("currency0", hex::encode(topics.address(2)?))
+        .with("currency1", hex::encode(topics.address(3)?))
+        .with("fee", initialize.fee)
+        .with("tick_spacing", initialize.tick_spacing)
+        .with("hooks", hex::encode(initialize.hooks))
+        .with("sqrt_price_x96", decode::numeric(initialize.sqrt_price_x96))
+        .with("tick", initialize.tick))
+}
+
+#[cfg(any(test, feature = "pg_test"))]
+#[pg_schema]
+mod tests {
+    use pgrx::datum::DatumWithOid;
+    use pgrx::prelude::*;
+
+    use std::str::FromStr;
+
+    use anyhow::Result;
+
+    use crate::uniswap::SwapAction;
+
+    #[pg_test]
+    fn univ4_test_pool_id() -> Result<()> {
+        // ETH / USDC 0.05% on mainnet, currencies are sorted so the order does not matter
+        let pool_id = Spi::get_one::<String>(
+            "SELECT UniswapV4.pool_id('0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48', '0x0000000000000000000000000000000000000000', 500, 10);",
+        )?;
+
+        assert_eq!(
+            pool_id,
+            Some(String::from(
+                "21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"
+            ))
+        );
+
+        let topic = Spi::get_one::<String>(
+            "SELECT UniswapV4.pool_id('{0x40e9cecb9f5f1f1c5b9c97dec2917b7ee92e57ba5563708daca94dd84ad7112f,0x21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27}'::text[]);",
+        )?;
+
+        assert_eq!(topic, pool_id);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn univ4_test_swap_price() -> Result<()> {
+        let data = "000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000c0fffffffffffffffffffffffffffffffffffffffffffffffff21f494c589c000000000000000000000000000000000000000000000000000000000000a4fc54000000000000000000000000000000000000000000000375631049ada5b0b4996a000000000000000000000000000000000000000000000000016345785d8a0000fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcfe7800000000000000000000000000000000000000000000000000000000000001f4";
+
+        let price = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT UniswapV4.swap_price($1, 18, 6);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(price, Some(pgrx::AnyNumeric::from_str("2785.008071")?));
+
+        let short =
+            Spi::get_one::<pgrx::AnyNumeric>("SELECT UniswapV4.try_swap_price('0020', 18, 6);")?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn univ4_test_decode_swap() -> Result<()> {
+        let topics = "{0x40e9cecb9f5f1f1c5b9c97dec2917b7ee92e57ba5563708daca94dd84ad7112f,0x21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27,0x0000000000000000000000001111111111111111111111111111111111111111}";
+        let data = "000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000c0fffffffffffffffffffffffffffffffffffffffffffffffff21f494c589c000000000000000000000000000000000000000000000000000000000000a4fc54000000000000000000000000000000000000000000000375631049ada5b0b4996a000000000000000000000000000000000000000000000000016345785d8a0000fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcfe7800000000000000000000000000000000000000000000000000000000000001f4";
+
+        let (pool_id, sender, action) = Spi::get_three_with_args::<String, String, i32>(
+            "SELECT pool_id, sender, action FROM UniswapV4.decode_swap($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            pool_id,
+            Some(String::from(
+                "21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27"
+            ))
+        );
+        assert_eq!(
+            sender,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+        assert_eq!(action, Some(SwapAction::SELL as i32));
+
+        let (base_amount, quote_amount) =
+            Spi::get_two_with_args::<pgrx::AnyNumeric, pgrx::AnyNumeric>(
+                "SELECT base_amount, quote_amount FROM UniswapV4.decode_swap($1::text[], $2);",
+                &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+            )?;
+
+        assert_eq!(
+            base_amount,
+            Some(pgrx::AnyNumeric::from_str("1000000000000000000")?)
+        );
+        assert_eq!(
+            quote_amount,
+            Some(pgrx::AnyNumeric::from_str("2768000000")?)
+        );
+
+        let (tick, fee) = Spi::get_two_with_args::<i32, i32>(
+            "SELECT tick, fee FROM UniswapV4.decode_swap($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(tick, Some(-197000));
+        assert_eq!(fee, Some(500));
+
+        let missing = Spi::get_one_with_args::<String>(
+            "SELECT pool_id FROM UniswapV4.try_decode_swap('{}'::text[], $1);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(missing, None);
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn univ4_test_decode_modify_liquidity() -> Result<()> {
+        let topics = "{0xf208f4912782fd25c7f114ca3723a2d5dd6f3bcc3ac8db5af63baa85f711d5ec,0x21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27,0x0000000000000000000000001111111111111111111111111111111111111111}";
+        let data = "00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000080fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcfe6efffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcfe82ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffec780000000000000000000000000000000000000000000000000000000000000000";
+
+        let (tick_lower, tick_upper, delta) =
+            Spi::get_three_with_args::<i32, i32, pgrx::AnyNumeric>(
+                "SELECT tick_lower, tick_upper, liquidity_delta FROM UniswapV4.decode_modify_liquidity($1::text[], $2);",
+                &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+            )?;
+
+        assert_eq!(tick_lower, Some(-197010));
+        assert_eq!(tick_upper, Some(-196990));
+        assert_eq!(delta, Some(pgrx::AnyNumeric::from_str("-5000")?));
+
+        let sender = Spi::get_one_with_args::<String>(
+            "SELECT sender FROM UniswapV4.decode_modify_liquidity($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            sender,
+            Some(String::from("1111111111111111111111111111111111111111"))
+        );
+
+        Ok(())
+    }
+
+    #[pg_test]
+    fn univ4_test_decode_initialize() -> Result<()> {
+        let topics = "{0xdd466e674ea557f56295e2d0218a125ea4b4f0f6f3307b95f85e6110838d6438,0x21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27,0x0000000000000000000000000000000000000000000000000000000000000000,0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48}";
+        let data = "000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000a000000000000000000000000000000000000000000000000000000000000001f4000000000000000000000000000000000000000000000000000000000000000a00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000375631049ada5b0b4996afffffffffffffffffffffffffffffffffffffffffffffffffffffffffffcfe78";
+
+        let (currency1, fee, tick_spacing) = Spi::get_three_with_args::<String, i32, i32>(
+            "SELECT currency1, fee, tick_spacing FROM UniswapV4.decode_initialize($1::text[], $2);",
+            &vec![DatumWithOid::from(topics), DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(
+            currency1,
+            Some(String::from("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"))
+        );
+        assert_eq!(fee, Some(500));
+        assert_eq!(tick_spacing, Some(10));
+
+        let price = Spi::get_one_with_args::<pgrx::AnyNumeric>(
+            "SELECT UniswapV4.initialize_price($1, 18, 6);",
+            &vec![DatumWithOid::from(data)],
+        )?;
+
+        assert_eq!(price, Some(pgrx::AnyNumeric::from_str("2785.008071")?));
+
+        let short = Spi::get_one_with_args::<i32>(
+            "SELECT tick FROM UniswapV4.try_decode_initialize($1::text[], '0020');",
+            &vec![DatumWithOid::from(topics)],
+        )?;
+
+        assert_eq!(short, None);
+
+        Ok(())
+    }
+
+    #[pg_test(error = "invalid fee: 16777216")]
+    fn univ4_test_pool_id_invalid_fee() {
+        Spi::run(
+            "SELECT UniswapV4.pool_id('0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48', '0x0000000000000000000000000000000000000000', 16777216, 10);",
+        )
+        .unwrap();
+    }
+}
@@ -1,9 +1,9 @@
 use pgrx::prelude::*;
 
 use bigdecimal::BigDecimal;
-use num::{bigint::Sign, BigInt};
+use num::{bigint::Sign, BigInt, Zero};
 
-use anyhow::Result;
+use crate::decode::{self, Error, Result, With};
 
 pub enum SwapAction {
     SELL = -1,
@@ -26,85 +26,305 @@ pub struct Sync {
 mod Velodrome {
     use pgrx::prelude::*;
 
-    use std::error::Error;
-    use std::str::FromStr;
-
     use alloy::core::hex;
 
-    use super::decode_swap;
-    use super::decode_sync;
-    use super::sync_price;
+    use super::{decode_swap, decode_sync, swap_row, sync_price, sync_row};
+    use crate::decode::{self, OrReport, Topics};
+
+    extension_sql!(
+        r#"
+CREATE TYPE Velodrome.swap AS (
+    action integer,
+    base_amount numeric,
+    quote_amount numeric
+);
+
+CREATE TYPE Velodrome.sync AS (
+    base_reserve numeric,
+    quote_reserve numeric
+);
+"#,
+        name = "velodrome_types",
+    );
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["velodrome_types"])]
+    fn velo_decode_swap(data: &str) -> pgrx::composite_type!('static, "Velodrome.swap") {
+        swap_row(decode_swap(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "decode_swap", immutable, parallel_safe, requires = ["velodrome_types"])]
+    fn velo_decode_swap_bytea(data: &[u8]) -> pgrx::composite_type!('static, "Velodrome.swap") {
+        swap_row(decode_swap(data).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["velodrome_types"]
+    )]
+    fn velo_try_decode_swap(
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Velodrome.swap")> {
+        decode_swap(&decode::hex(data).ok()?).ok().map(swap_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_swap",
+        immutable,
+        parallel_safe,
+        requires = ["velodrome_types"]
+    )]
+    fn velo_try_decode_swap_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Velodrome.swap")> {
+        decode_swap(data).ok().map(swap_row)
+    }
+
+    #[pg_extern(name = "decode_sync", immutable, parallel_safe, requires = ["velodrome_types"])]
+    fn velo_decode_sync(data: &str) -> pgrx::composite_type!('static, "Velodrome.sync") {
+        sync_row(decode_sync(&decode::hex(data).or_report()).or_report())
+    }
+
+    #[pg_extern(name = "decode_sync", immutable, parallel_safe, requires = ["velodrome_types"])]
+    fn velo_decode_sync_bytea(data: &[u8]) -> pgrx::composite_type!('static, "Velodrome.sync") {
+        sync_row(decode_sync(data).or_report())
+    }
+
+    #[pg_extern(
+        name = "try_decode_sync",
+        immutable,
+        parallel_safe,
+        requires = ["velodrome_types"]
+    )]
+    fn velo_try_decode_sync(
+        data: &str,
+    ) -> Option<pgrx::composite_type!('static, "Velodrome.sync")> {
+        decode_sync(&decode::hex(data).ok()?).ok().map(sync_row)
+    }
+
+    #[pg_extern(
+        name = "try_decode_sync",
+        immutable,
+        parallel_safe,
+        requires = ["velodrome_types"]
+    )]
+    fn velo_try_decode_sync_bytea(
+        data: &[u8],
+    ) -> Option<pgrx::composite_type!('static, "Velodrome.sync")> {
+        decode_sync(data).ok().map(sync_row)
+    }
+
+    #[pg_extern(name = "swap_sender", immutable, parallel_safe)]
+    fn velo_swap_sender(topics: Array<&str>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "swap_sender_raw", immutable, parallel_safe)]
+    fn velo_swap_sender_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(1).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_sender", immutable, parallel_safe)]
+    fn velo_try_swap_sender(topics: Array<&str>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_swap_sender_raw", immutable, parallel_safe)]
+    fn velo_try_swap_sender_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(1).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "swap_recipient", immutable, parallel_safe)]
+    fn velo_swap_recipient(topics: Array<&str>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "swap_recipient_raw", immutable, parallel_safe)]
+    fn velo_swap_recipient_raw(topics: Array<&[u8]>) -> String {
+        hex::encode(topics.address(2).or_report())
+    }
+
+    #[pg_extern(name = "try_swap_recipient", immutable, parallel_safe)]
+    fn velo_try_swap_recipient(topics: Array<&str>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
+
+    #[pg_extern(name = "try_swap_recipient_raw", immutable, parallel_safe)]
+    fn velo_try_swap_recipient_raw(topics: Array<&[u8]>) -> Option<String> {
+        topics.address(2).ok().map(hex::encode)
+    }
 
     #[pg_extern(name = "swap_type", immutable, parallel_safe)]
     fn velo_swap_type(data: &str) -> i32 {
-        decode_swap(&hex::decode(data).unwrap()).unwrap().action as i32
+        decode_swap(&decode::hex(data).or_report())
+            .or_report()
+            .action as i32
+    }
+
+    #[pg_extern(name = "swap_type", immutable, parallel_safe)]
+    fn velo_swap_type_bytea(data: &[u8]) -> i32 {
+        decode_swap(data).or_report().action as i32
+    }
+
+    #[pg_extern(name = "try_swap_type", immutable, parallel_safe)]
+    fn velo_try_swap_type(data: &str) -> Option<i32> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(swap.action as i32)
+    }
+
+    #[pg_extern(name = "try_swap_type", immutable, parallel_safe)]
+    fn velo_try_swap_type_bytea(data: &[u8]) -> Option<i32> {
+        let swap = decode_swap(data).ok()?;
+        Some(swap.action as i32)
     }
 
     #[pg_extern(name = "swap_base_amount", immutable, parallel_safe)]
-    fn velo_swap_base_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_swap(&hex::decode(data).unwrap())
-                .unwrap()
-                .base_amount
-                .to_string()
-                .as_str(),
-        )?)
+    fn velo_swap_base_amount(data: &str) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(swap.base_amount)
+    }
+
+    #[pg_extern(name = "swap_base_amount", immutable, parallel_safe)]
+    fn velo_swap_base_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(swap.base_amount)
+    }
+
+    #[pg_extern(name = "try_swap_base_amount", immutable, parallel_safe)]
+    fn velo_try_swap_base_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(swap.base_amount))
+    }
+
+    #[pg_extern(name = "try_swap_base_amount", immutable, parallel_safe)]
+    fn velo_try_swap_base_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(swap.base_amount))
+    }
+
+    #[pg_extern(name = "swap_quote_amount", immutable, parallel_safe)]
+    fn velo_swap_quote_amount(data: &str) -> pgrx::AnyNumeric {
+        let swap = decode_swap(&decode::hex(data).or_report()).or_report();
+        decode::numeric(swap.quote_amount)
     }
 
     #[pg_extern(name = "swap_quote_amount", immutable, parallel_safe)]
-    fn velo_swap_quote_amount(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_swap(&hex::decode(data).unwrap())
-                .unwrap()
-                .quote_amount
-                .to_string()
-                .as_str(),
-        )?)
+    fn velo_swap_quote_amount_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let swap = decode_swap(data).or_report();
+        decode::numeric(swap.quote_amount)
+    }
+
+    #[pg_extern(name = "try_swap_quote_amount", immutable, parallel_safe)]
+    fn velo_try_swap_quote_amount(data: &str) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(swap.quote_amount))
+    }
+
+    #[pg_extern(name = "try_swap_quote_amount", immutable, parallel_safe)]
+    fn velo_try_swap_quote_amount_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let swap = decode_swap(data).ok()?;
+        Some(decode::numeric(swap.quote_amount))
     }
 
     #[pg_extern(name = "sync_base_reserve", immutable, parallel_safe)]
-    fn velo_sync_base_reserve(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_sync(&hex::decode(data).unwrap())
-                .unwrap()
-                .base_reserve
-                .to_string()
-                .as_str(),
-        )?)
+    fn velo_sync_base_reserve(data: &str) -> pgrx::AnyNumeric {
+        let sync = decode_sync(&decode::hex(data).or_report()).or_report();
+        decode::numeric(sync.base_reserve)
+    }
+
+    #[pg_extern(name = "sync_base_reserve", immutable, parallel_safe)]
+    fn velo_sync_base_reserve_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let sync = decode_sync(data).or_report();
+        decode::numeric(sync.base_reserve)
+    }
+
+    #[pg_extern(name = "try_sync_base_reserve", immutable, parallel_safe)]
+    fn velo_try_sync_base_reserve(data: &str) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(sync.base_reserve))
+    }
+
+    #[pg_extern(name = "try_sync_base_reserve", immutable, parallel_safe)]
+    fn velo_try_sync_base_reserve_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(data).ok()?;
+        Some(decode::numeric(sync.base_reserve))
     }
 
     #[pg_extern(name = "sync_quote_reserve", immutable, parallel_safe)]
-    fn velo_sync_quote_reserve(data: &str) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            decode_sync(&hex::decode(data).unwrap())
-                .unwrap()
-                .quote_reserve
-                .to_string()
-                .as_str(),
-        )?)
+    fn velo_sync_quote_reserve(data: &str) -> pgrx::AnyNumeric {
+        let sync = decode_sync(&decode::hex(data).or_report()).or_report();
+        decode::numeric(sync.quote_reserve)
+    }
+
+    #[pg_extern(name = "sync_quote_reserve", immutable, parallel_safe)]
+    fn velo_sync_quote_reserve_bytea(data: &[u8]) -> pgrx::AnyNumeric {
+        let sync = decode_sync(data).or_report();
+        decode::numeric(sync.quote_reserve)
+    }
+
+    #[pg_extern(name = "try_sync_quote_reserve", immutable, parallel_safe)]
+    fn velo_try_sync_quote_reserve(data: &str) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(&decode::hex(data).ok()?).ok()?;
+        Some(decode::numeric(sync.quote_reserve))
+    }
+
+    #[pg_extern(name = "try_sync_quote_reserve", immutable, parallel_safe)]
+    fn velo_try_sync_quote_reserve_bytea(data: &[u8]) -> Option<pgrx::AnyNumeric> {
+        let sync = decode_sync(data).ok()?;
+        Some(decode::numeric(sync.quote_reserve))
+    }
+
+    #[pg_extern(name = "sync_price", immutable, parallel_safe)]
+    fn velo_sync_price(data: &str, base_decimals: i64, quote_decimals: i64) -> pgrx::AnyNumeric {
+        let price = sync_price(
+            &decode::hex(data).or_report(),
+            base_decimals,
+            quote_decimals,
+        )
+        .or_report();
+
+        decode::numeric(price)
     }
 
     #[pg_extern(name = "sync_price", immutable, parallel_safe)]
-    fn velo_sync_price(
+    fn velo_sync_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> pgrx::AnyNumeric {
+        let price = sync_price(data, base_decimals, quote_decimals).or_report();
+
+        decode::numeric(price)
+    }
+
+    #[pg_extern(name = "try_sync_price", immutable, parallel_safe)]
+    fn velo_try_sync_price(
         data: &str,
         base_decimals: i64,
         quote_decimals: i64,
-    ) -> Result<pgrx::AnyNumeric, Box<dyn Error>> {
-        Ok(pgrx::AnyNumeric::from_str(
-            sync_price(&hex::decode(data).unwrap(), base_decimals, quote_decimals)
-                .to_string()
-                .as_str(),
-        )?)
+    ) -> Option<pgrx::AnyNumeric> {
+        let price = sync_price(&decode::hex(data).ok()?, base_decimals, quote_decimals).ok()?;
+        Some(decode::numeric(price))
+    }
+
+    #[pg_extern(name = "try_sync_price", immutable, parallel_safe)]
+    fn velo_try_sync_price_bytea(
+        data: &[u8],
+        base_decimals: i64,
+        quote_decimals: i64,
+    ) -> Option<pgrx::AnyNumeric> {
+        let price = sync_price(data, base_decimals, quote_decimals).ok()?;
+        Some(decode::numeric(price))
     }
 }
 
-#[allow(dead_code)]
-fn decode_swap(data: &[u8]) -> Result<Swap> {
-    let amount_0_in = BigInt::from_bytes_be(Sign::Plus, &data[64..96]);
-    let amount_1_in = BigInt::from_bytes_be(Sign::Plus, &data[96..128]);
+pub fn decode_swap(data: &[u8]) -> Result<Swap> {
+    let amount_0_in = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 64, 96)?);
+    let amount_1_in = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 96, 128)?);
 
-    let amount_0_out = BigInt::from_bytes_be(Sign::Plus, &data[128..160]);
-    let amount_1_out = BigInt::from_bytes_be(Sign::Plus, &data[160..192]);
+    let amount_0_out = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 128, 160)?);
+    let amount_1_out = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 160, 192)?);
 
     let action = match amount_0_in.gt(&BigInt::from(0u32)) {
         true => SwapAction::SELL,
@@ -121,10 +341,9 @@ fn decode_swap(data: &[u8]) -> Result<Swap> {
     })
 }
 
-#[allow(dead_code)]
 fn decode_sync(data: &[u8]) -> Result<Sync> {
-    let base_reserve = BigInt::from_bytes_be(Sign::Plus, &data[64..96]);
-    let quote_reserve = BigInt::from_bytes_be(Sign::Plus, &data[96..128]);
+    let base_reserve = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 64, 96)?);
+    let quote_reserve = BigInt::from_bytes_be(Sign::Plus, decode::slice(data, 96, 128)?);
 
     Ok(Sync {
         base_reserve,
@@ -132,14 +351,33 @@ fn decode_sync(data: &[u8]) -> Result<Sync> {
     })
 }
 
-fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> BigDecimal {
-    let base_reserve = BigInt::from_bytes_be(Sign::Plus, &bytes[64..96]);
-    let quote_reserve = BigInt::from_bytes_be(Sign::Plus, &bytes[96..128]);
+fn sync_price(bytes: &[u8], base_decimals: i64, quote_decimals: i64) -> Result<BigDecimal> {
+    let Sync {
+        base_reserve,
+        quote_reserve,
+    } = decode_sync(bytes)?;
+
+    if base_reserve.is_zero() {
+        return Err(Error::data("base reserve is zero"));
+    }
 
     let decimal_base_reserve = BigDecimal::new(base_reserve, base_decimals);
     let decimal_quote_reserve = BigDecimal::new(quote_reserve, quote_decimals);
 
-    return (decimal_quote_reserve / decimal_base_reserve).round(quote_decimals);
+    Ok((decimal_quote_reserve / decimal_base_reserve).round(quote_decimals))
+}
+
+fn swap_row(swap: Swap) -> decode::Row {
+    decode::row("Velodrome.swap")
+        .with("action", swap.action as i32)
+        .with("base_amount", decode::numeric(swap.base_amount))
+        .with("quote_amount", decode::numeric(swap.quote_amount))
+}
+
+fn sync_row(sync: Sync) -> decode::Row {
+    decode::row("Velodrome.sync")
+        .with("base_reserve", decode::numeric(sync.base_reserve))
+        .with("quote_reserve", decode::numeric(sync.quote_reserve))
 }
 
 #[cfg(any(test, feature = "pg_test"))]